@@ -0,0 +1,52 @@
+use std::{io, process::ExitCode};
+
+use advent_of_code_2015::{
+    day14::{parse, Race},
+    YEAR,
+};
+use advent_of_code_common::inputs;
+
+const USAGE: &str = "Usage: day14-race [--duration N] [FILE]
+
+Runs the 2015 day 14 race of FILE (default: the cached input) for N seconds (default: 2503)
+and prints it as CSV, one line per second with the leaders, whether the lead changed, and
+every reindeer's distance and points.";
+
+fn main() -> ExitCode {
+    let mut duration = 2503;
+    let mut path = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--duration" => match args.next().and_then(|n| n.parse().ok()) {
+                Some(n) => duration = n,
+                None => {
+                    eprintln!("--duration expects a number\n\n{USAGE}");
+                    return ExitCode::from(2);
+                }
+            },
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(arg),
+            _ => {
+                eprintln!("unexpected argument '{arg}'\n\n{USAGE}");
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    let input = inputs::load_for(env!("CARGO_MANIFEST_DIR"), path.as_deref(), YEAR, 14);
+
+    let result = input.and_then(|input| {
+        let reindeer = parse(input.trim_end()).map_err(|e| format!("{e}\n{}", e.snippet()))?;
+        Race::new(&reindeer, duration)
+            .write_csv(io::stdout().lock())
+            .map_err(|e| e.to_string())
+    });
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use std::process::ExitCode;
+
+use advent_of_code_2015::{
+    day19::{derive, parse, verify},
+    YEAR,
+};
+use advent_of_code_common::inputs;
+
+const USAGE: &str = "Usage: day19-derive [FILE]
+
+Prints the shortest derivation of the 2015 day 19 molecule of FILE (default: the cached input)
+from e, one replacement per line with the byte it applies at, then replays it to check it.";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let input = match &args[..] {
+        [] => inputs::load_for(env!("CARGO_MANIFEST_DIR"), None, YEAR, 19),
+        [path] if !path.starts_with("--") => {
+            inputs::load_for(env!("CARGO_MANIFEST_DIR"), Some(path), YEAR, 19)
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let result = input.and_then(|input| {
+        let machine = parse(input.trim_end()).map_err(|e| format!("{e}\n{}", e.snippet()))?;
+        let steps = derive(&machine).map_err(|e| e.to_string())?;
+        for (n, step) in steps.iter().enumerate() {
+            println!("{:>4}. {step}", n + 1);
+        }
+        verify(&machine, &steps).map_err(|e| format!("invalid derivation, {e}"))?;
+        Ok(steps.len())
+    });
+
+    match result {
+        Ok(steps) => {
+            println!("{steps} steps, verified");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
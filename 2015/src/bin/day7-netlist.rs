@@ -0,0 +1,76 @@
+use std::process::ExitCode;
+
+use advent_of_code_2015::{
+    day7::{parse, Circuit, CircuitError},
+    YEAR,
+};
+use advent_of_code_common::inputs;
+
+const USAGE: &str = "Usage: day7-netlist [--values] [FILE]
+       day7-netlist --diff OTHER [FILE]
+
+Prints the 2015 day 7 circuit of FILE (default: the cached input) as Graphviz DOT.
+
+  --values      label every wire with its signal
+  --diff OTHER  list the gates OTHER changes and the signals that change with them";
+
+fn load(path: Option<&str>) -> Result<Circuit, String> {
+    let input = inputs::load_for(env!("CARGO_MANIFEST_DIR"), path, YEAR, 7)?;
+
+    parse(&input).map_err(|e| match e {
+        CircuitError::Parse(e) => format!("{e}\n{}", e.snippet()),
+        e => e.to_string(),
+    })
+}
+
+fn main() -> ExitCode {
+    let mut values = false;
+    let mut other = None;
+    let mut path = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--values" => values = true,
+            "--diff" => match args.next() {
+                Some(file) => other = Some(file),
+                None => {
+                    eprintln!("--diff expects a file\n\n{USAGE}");
+                    return ExitCode::from(2);
+                }
+            },
+            _ if path.is_none() => path = Some(arg),
+            _ => {
+                eprintln!("unexpected argument '{arg}'\n\n{USAGE}");
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    let result = load(path.as_deref()).and_then(|circuit| match &other {
+        Some(other) => {
+            let diff = circuit.diff(&load(Some(other))?);
+            Ok(if diff.is_empty() {
+                "The circuits are identical\n".to_string()
+            } else {
+                diff.to_string()
+            })
+        }
+        None if values => {
+            let signals = circuit.simulate(&[]).map_err(|e| e.to_string())?;
+            Ok(circuit.to_dot(Some(&signals)))
+        }
+        None => Ok(circuit.to_dot(None)),
+    });
+
+    match result {
+        Ok(output) => {
+            print!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
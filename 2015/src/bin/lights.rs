@@ -0,0 +1,169 @@
+use std::process::ExitCode;
+
+use advent_of_code_2015::{day18, day6, YEAR};
+use advent_of_code_common::inputs;
+use grid::{
+    automaton::{Rule, RuleError, Topology},
+    image::Animation,
+};
+
+const USAGE: &str = "Usage: lights day6 [--brightness] [--scale N] [--input FILE] OUTPUT
+       lights day18 [--stuck] [--rule RULE] [--torus] [--steps N] [--final] [--scale N]
+                    [--input FILE] OUTPUT
+
+Draws the 2015 day 6 or day 18 lights (default input: the cached one) to OUTPUT, whose
+extension picks the format: .pgm, .ppm, .png or .gif.
+
+  --brightness  shade the day 6 lights by their part 2 brightness
+  --stuck       keep the four corners of the day 18 grid on, as in part 2
+  --rule RULE   run day 18 under another life-like rule (default: B3/S23)
+  --torus       wrap the day 18 grid around its edges
+  --steps N     animate N day 18 steps (default: 100); a .gif holds them all, other formats
+                get a numbered image per step (OUTPUT-0000.png...)
+  --final       only draw the day 18 grid after the last step
+  --scale N     draw each light as an N by N square (default: 1 for day 6, 4 for day 18)
+  --input FILE  read the puzzle input from FILE";
+
+struct Options {
+    day: u8,
+    brightness: bool,
+    stuck: bool,
+    rule: Rule,
+    topology: Topology,
+    steps: usize,
+    last_only: bool,
+    scale: Option<usize>,
+    input: Option<String>,
+    output: String,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let day = match args.next().as_deref() {
+        Some("day6") => 6,
+        Some("day18") => 18,
+        Some(other) => return Err(format!("unknown day '{other}'")),
+        None => return Err("missing day".to_string()),
+    };
+
+    let mut options = Options {
+        day,
+        brightness: false,
+        stuck: false,
+        rule: Rule::LIFE,
+        topology: Topology::Bounded,
+        steps: 100,
+        last_only: false,
+        scale: None,
+        input: None,
+        output: String::new(),
+    };
+    let number = |flag: &str, value: Option<String>| {
+        value
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| format!("{flag} expects a number"))
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--brightness" if day == 6 => options.brightness = true,
+            "--stuck" if day == 18 => options.stuck = true,
+            "--rule" if day == 18 => {
+                let rule = args.next().ok_or("--rule expects a rule such as B36/S23")?;
+                options.rule = rule.parse().map_err(|e: RuleError| e.to_string())?;
+            }
+            "--torus" if day == 18 => options.topology = Topology::Toroidal,
+            "--steps" if day == 18 => options.steps = number("--steps", args.next())?,
+            "--final" if day == 18 => options.last_only = true,
+            "--scale" => options.scale = Some(number("--scale", args.next())?),
+            "--input" => {
+                options.input = Some(args.next().ok_or("--input expects a file")?);
+            }
+            _ if options.output.is_empty() && !arg.starts_with("--") => options.output = arg,
+            _ => return Err(format!("unexpected argument '{arg}'")),
+        }
+    }
+
+    if options.output.is_empty() {
+        return Err("missing output file".to_string());
+    }
+    Ok(options)
+}
+
+fn draw(options: &Options) -> Result<String, String> {
+    let input = inputs::load_for(
+        env!("CARGO_MANIFEST_DIR"),
+        options.input.as_deref(),
+        YEAR,
+        options.day,
+    )?;
+    let output = &options.output;
+    let error = |e: std::io::Error| format!("{output}: {e}");
+
+    if options.day == 6 {
+        let instructions = day6::parse(&input).map_err(|e| format!("{e}\n{}", e.snippet()))?;
+        day6::picture(&instructions, options.brightness)
+            .scaled(options.scale.unwrap_or(1))
+            .write_image(output)
+            .map_err(error)?;
+        return Ok(format!("Wrote {output}"));
+    }
+
+    let scale = options.scale.unwrap_or(4);
+    let mut grid = day18::Grid::with_rule(input.trim_end(), options.rule, options.topology)
+        .map_err(|e| match &e {
+            day18::LightsError::Parse(parse) => format!("{e}\n{}", parse.snippet()),
+            _ => e.to_string(),
+        })?;
+    if options.stuck {
+        grid.stick_corners();
+    }
+
+    if options.last_only {
+        for _ in 0..options.steps {
+            grid.step();
+        }
+        grid.picture()
+            .scaled(scale)
+            .write_image(output)
+            .map_err(error)?;
+        return Ok(format!("Wrote {output}, {} lights on", grid.count_on()));
+    }
+
+    let mut animation = Animation::new(output).map_err(error)?;
+    animation
+        .push(&grid.picture().scaled(scale))
+        .map_err(error)?;
+    for _ in 0..options.steps {
+        grid.step();
+        animation
+            .push(&grid.picture().scaled(scale))
+            .map_err(error)?;
+    }
+    let frames = animation.finish().map_err(error)?;
+
+    Ok(format!(
+        "Wrote {frames} frames to {output}, {} lights on at the end",
+        grid.count_on()
+    ))
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match draw(&options) {
+        Ok(summary) => {
+            println!("{summary}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
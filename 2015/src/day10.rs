@@ -1,10 +1,15 @@
+use std::collections::{HashMap, VecDeque};
+
+use advent_of_code_common::big::BigUint;
 use aoc_runner_derive::aoc;
 
 fn counter(input: &str) -> String {
     let mut chars = input.chars();
     let mut result = String::new();
 
-    let mut current = chars.next().unwrap();
+    let Some(mut current) = chars.next() else {
+        return String::new();
+    };
     let mut counter = 1;
 
     for c in chars {
@@ -21,22 +26,237 @@ fn counter(input: &str) -> String {
     result
 }
 
-#[aoc(day10, part1)]
-fn part1(input: &str) -> u32 {
-    let mut output = input.to_owned();
-    for _ in 0..40 {
-        output = counter(&output)
+/// The runs of a string as `(digit, length)`
+fn runs(digits: &[u8]) -> Vec<(u8, usize)> {
+    digits
+        .chunk_by(|a, b| a == b)
+        .map(|run| (run[0], run.len()))
+        .collect()
+}
+
+/// Conway's Splitting Theorem: a string at least two steps old splits between a part ending in
+/// `last` and the `rest` of the string when their descendants can never run into each other
+fn splits(last: u8, rest: &[(u8, usize)]) -> bool {
+    let high = |digit: u8| !matches!(digit, b'1'..=b'3');
+    let after_two = |rest: &[(u8, usize)]| match *rest {
+        [(b'1', 1), (_, 1), ..] | [(b'1', 3), ..] => true,
+        [(b'3', 1)] => true,
+        [(b'3', 1), (_, length), ..] => length != 3,
+        [(digit, _), ..] => high(digit),
+        [] => false,
+    };
+
+    match (last, rest) {
+        (_, [(first, _), ..]) if high(last) && !high(*first) => true,
+        (b'2', _) => after_two(rest),
+        (_, [(b'2', 2), tail @ ..]) => after_two(tail),
+        _ => false,
     }
-    output.len() as u32
 }
 
-#[aoc(day10, part2)]
-fn part2(input: &str) -> u32 {
-    let mut output = input.to_owned();
-    for _ in 0..50 {
-        output = counter(&output)
+/// Cuts a string into the atoms that evolve independently of each other
+fn atoms(digits: &[u8]) -> Vec<&[u8]> {
+    let runs = runs(digits);
+    let mut atoms = vec![];
+    let (mut start, mut end) = (0, 0);
+
+    for (i, &(digit, length)) in runs.iter().enumerate() {
+        end += length;
+        if i + 1 < runs.len() && splits(digit, &runs[i + 1..]) {
+            atoms.push(&digits[start..end]);
+            start = end;
+        }
+    }
+    if start < digits.len() {
+        atoms.push(&digits[start..]);
+    }
+
+    atoms
+}
+
+/// Every atom a seed's descendants are made of, with the atoms each one becomes a step later:
+/// Conway's 92 elements, the transuranic ones for seeds with digits above 3, and whatever short
+/// lived atoms the seed starts with
+#[derive(Debug, Default)]
+struct Chemistry {
+    atoms: Vec<Vec<u8>>,
+    ids: HashMap<Vec<u8>, usize>,
+    decay: Vec<Vec<(usize, u32)>>,
+}
+
+impl Chemistry {
+    fn id(&mut self, atom: &[u8], queue: &mut VecDeque<usize>) -> usize {
+        if let Some(&id) = self.ids.get(atom) {
+            return id;
+        }
+        let id = self.atoms.len();
+        self.atoms.push(atom.to_vec());
+        self.ids.insert(atom.to_vec(), id);
+        queue.push_back(id);
+        id
+    }
+
+    /// The chemistry of the string `start`, at least two steps old, with the count of each atom
+    /// in it
+    fn of(start: &str) -> (Chemistry, Vec<u32>) {
+        let mut chemistry = Chemistry::default();
+        let mut queue = VecDeque::new();
+
+        let mut counts = vec![];
+        for atom in atoms(start.as_bytes()) {
+            let id = chemistry.id(atom, &mut queue);
+            counts.resize(counts.len().max(id + 1), 0);
+            counts[id] += 1;
+        }
+
+        while let Some(id) = queue.pop_front() {
+            let next = counter(std::str::from_utf8(&chemistry.atoms[id]).unwrap());
+            let mut decay: Vec<(usize, u32)> = vec![];
+            for atom in atoms(next.as_bytes()) {
+                let product = chemistry.id(atom, &mut queue);
+                match decay.iter_mut().find(|(id, _)| *id == product) {
+                    Some((_, count)) => *count += 1,
+                    None => decay.push((product, 1)),
+                }
+            }
+            chemistry.decay.resize(chemistry.atoms.len(), vec![]);
+            chemistry.decay[id] = decay;
+        }
+
+        counts.resize(chemistry.atoms.len(), 0);
+        (chemistry, counts)
+    }
+
+    /// One step of every atom at once: the transition matrix applied to the atom counts
+    fn step(&self, counts: &[BigUint]) -> Vec<BigUint> {
+        let mut next = vec![BigUint::zero(); counts.len()];
+        for (id, count) in counts.iter().enumerate().filter(|(_, c)| !c.is_zero()) {
+            for &(product, times) in &self.decay[id] {
+                next[product] += &(count * times);
+            }
+        }
+        next
+    }
+}
+
+/// The length of the string after `steps` rounds of look-and-say from `seed`, going through the
+/// atoms it splits into rather than the string itself, so that thousands of steps are cheap
+pub fn length(seed: &str, steps: usize) -> BigUint {
+    let mut start = seed.to_string();
+    for _ in 0..steps.min(2) {
+        start = counter(&start);
+    }
+    if steps <= 2 {
+        return BigUint::from(start.len());
+    }
+
+    let (chemistry, counts) = Chemistry::of(&start);
+    let mut counts: Vec<BigUint> = counts
+        .into_iter()
+        .map(|c| BigUint::from(c as usize))
+        .collect();
+    for _ in 2..steps {
+        counts = chemistry.step(&counts);
+    }
+
+    counts
+        .iter()
+        .zip(&chemistry.atoms)
+        .map(|(count, atom)| count * atom.len() as u32)
+        .sum()
+}
+
+/// One round of look-and-say, reading from the round below it
+#[derive(Debug, Default)]
+struct Stage {
+    run: Option<(u8, usize)>,
+    pending: VecDeque<u8>,
+    finished: bool,
+}
+
+impl Stage {
+    /// Takes the next digit of the round below, or its end
+    fn feed(&mut self, digit: Option<u8>) {
+        match (self.run, digit) {
+            (Some((current, count)), Some(digit)) if current == digit => {
+                self.run = Some((current, count + 1))
+            }
+            (run, digit) => {
+                if let Some((current, count)) = run {
+                    self.pending.extend(count.to_string().bytes());
+                    self.pending.push_back(current);
+                }
+                self.run = digit.map(|digit| (digit, 1));
+                self.finished = digit.is_none();
+            }
+        }
+    }
+}
+
+/// The digits of the string `steps` rounds of look-and-say after a seed, produced one at a time
+/// through a pipeline of rounds, so that only a few digits of each round are ever held
+#[derive(Debug)]
+pub struct Digits {
+    seed: std::vec::IntoIter<u8>,
+    stages: Vec<Stage>,
+}
+
+pub fn digits(seed: &str, steps: usize) -> Digits {
+    Digits {
+        seed: seed.as_bytes().to_vec().into_iter(),
+        stages: (0..steps).map(|_| Stage::default()).collect(),
+    }
+}
+
+impl Iterator for Digits {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        let top = self.stages.len();
+        // Walks down to the first round with a digit to give, then carries it back up
+        let mut level = top;
+        let mut incoming = None;
+
+        loop {
+            if level == 0 {
+                incoming = Some(self.seed.next());
+                level = 1;
+                if top == 0 {
+                    return incoming.flatten().map(char::from);
+                }
+                continue;
+            }
+
+            let stage = &mut self.stages[level - 1];
+            if let Some(digit) = incoming.take() {
+                stage.feed(digit);
+            }
+
+            let out = match stage.pending.pop_front() {
+                Some(digit) => Some(digit),
+                None if stage.finished => None,
+                None => {
+                    level -= 1;
+                    continue;
+                }
+            };
+            if level == top {
+                return out.map(char::from);
+            }
+            incoming = Some(out);
+            level += 1;
+        }
     }
-    output.len() as u32
+}
+
+#[aoc(day10, part1)]
+fn part1(input: &str) -> BigUint {
+    length(input, 40)
+}
+
+#[aoc(day10, part2)]
+fn part2(input: &str) -> BigUint {
+    length(input, 50)
 }
 
 #[cfg(test)]
@@ -51,8 +271,57 @@ mod tests {
         assert_eq!(counter("111221"), "312211");
     }
 
-    // #[test]
-    // fn part2_example() {
-    //     assert_eq!(part2(&parse("1113122113")), "<RESULT>");
-    // }
+    #[test]
+    fn test_against_counter() {
+        for seed in [
+            "",
+            "1",
+            "1113122113",
+            "3113322113",
+            "22",
+            "1111111111",
+            "40",
+            "123456789",
+        ] {
+            let mut string = seed.to_string();
+            for steps in 0..=30 {
+                assert_eq!(
+                    length(seed, steps).to_u128(),
+                    Some(string.len() as u128),
+                    "{seed} after {steps}"
+                );
+                assert!(
+                    digits(seed, steps).eq(string.chars()),
+                    "{seed} after {steps}"
+                );
+                string = counter(&string);
+            }
+        }
+    }
+
+    #[test]
+    fn test_elements() {
+        // Whatever the seed, only Conway's 92 common elements are left after a while
+        let (chemistry, counts) = Chemistry::of(&counter(&counter("1")));
+        let mut counts: Vec<BigUint> = counts
+            .into_iter()
+            .map(|c| BigUint::from(c as usize))
+            .collect();
+        for _ in 0..100 {
+            counts = chemistry.step(&counts);
+        }
+        assert_eq!(counts.iter().filter(|c| !c.is_zero()).count(), 92);
+        assert_eq!(
+            chemistry.decay[chemistry.ids[b"22".as_slice()]],
+            [(chemistry.ids[b"22".as_slice()], 1)]
+        );
+    }
+
+    #[test]
+    fn test_long_run() {
+        let length = length("1113122113", 2000);
+        // Conway's constant: every step is about 30.36% longer
+        let ratio = (length.bits() as f64 - 1.0) / 2000.0;
+        assert!((ratio - 1.303577269f64.log2()).abs() < 0.01, "{ratio}");
+    }
 }
@@ -1,85 +1,224 @@
+use std::fmt::Display;
+
 use aoc_runner_derive::aoc;
-use itertools::Itertools;
 
-fn has_increasing(input: &str) -> bool {
-    for (a, b, c) in input.chars().tuple_windows::<(_, _, _)>() {
-        let na = std::char::from_u32(a as u32 + 1).unwrap();
-        let nb = std::char::from_u32(b as u32 + 1).unwrap();
-        if c == nb && b == na {
-            return true;
+/// What makes a password valid. The default is Santa's: no `i`, `o` or `l`, a straight of three
+/// letters such as `abc`, and two non-overlapping pairs such as `aa` and `bb`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    /// One bit per letter, `a` being the lowest
+    forbidden: u32,
+    straight: usize,
+    pairs: usize,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            forbidden: 0,
+            straight: 3,
+            pairs: 2,
         }
+        .forbid("iol")
     }
-
-    false
 }
 
-fn has_forbidden_chars(input: &str) -> bool {
-    input.contains('i') || input.contains('o') || input.contains('l')
-}
+impl Rules {
+    /// Forbids these letters, and only these. Anything but `a` to `z` is never in a password
+    /// anyway, so it is ignored.
+    pub fn forbid(mut self, letters: &str) -> Self {
+        self.forbidden = letters
+            .bytes()
+            .filter(u8::is_ascii_lowercase)
+            .fold(0, |mask, letter| mask | 1 << (letter - b'a'));
+        self
+    }
 
-fn different_pairs(input: &str) -> bool {
-    let mut count = 0;
-    let mut current = 1;
-    let chars = input.chars().collect::<Vec<char>>();
-    while current < chars.len() {
-        if chars[current] == chars[current - 1] {
-            count += 1;
-            current += 1
-        }
-        if count == 2 {
+    /// The length of the run of consecutive letters needed, 0 or 1 for none
+    pub fn straight(mut self, length: usize) -> Self {
+        self.straight = length;
+        self
+    }
+
+    /// How many non-overlapping pairs of the same letter are needed
+    pub fn pairs(mut self, count: usize) -> Self {
+        self.pairs = count;
+        self
+    }
+
+    fn is_forbidden(&self, letter: u8) -> bool {
+        self.forbidden & 1 << (letter - b'a') != 0
+    }
+
+    /// The first letter from `letter` on that is allowed
+    fn allowed_from(&self, letter: u8) -> Option<u8> {
+        (letter..=b'z').find(|&letter| !self.is_forbidden(letter))
+    }
+
+    fn has_forbidden(&self, password: &[u8]) -> bool {
+        password.iter().any(|&letter| self.is_forbidden(letter))
+    }
+
+    fn has_straight(&self, password: &[u8]) -> bool {
+        if self.straight <= 1 {
             return true;
         }
+        let mut run = 1;
+        for pair in password.windows(2) {
+            run = if pair[1] == pair[0] + 1 { run + 1 } else { 1 };
+            if run >= self.straight {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn has_pairs(&self, password: &[u8]) -> bool {
+        let mut count = 0;
+        let mut i = 1;
+        while count < self.pairs && i < password.len() {
+            if password[i] == password[i - 1] {
+                count += 1;
+                i += 1;
+            }
+            i += 1;
+        }
+        count >= self.pairs
+    }
 
-        current += 1;
+    pub fn is_valid(&self, password: &[u8]) -> bool {
+        !self.has_forbidden(password) && self.has_straight(password) && self.has_pairs(password)
     }
 
-    false
+    /// Whether some password of this width could be valid, checking only what is quick to
+    /// check, so that hopeless rules do not send the iterator through all `26^width` of them
+    fn can_be_met(&self, width: usize) -> bool {
+        let straight = self.straight.max(1);
+        let has_straight = (b'a'..=b'z')
+            .collect::<Vec<_>>()
+            .windows(straight)
+            .any(|letters| !self.has_forbidden(letters));
+        straight <= width && 2 * self.pairs <= width && has_straight
+    }
 }
 
-fn is_valid(input: &str) -> bool {
-    has_increasing(input) && !has_forbidden_chars(input) && different_pairs(input)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PasswordError {
+    /// A character other than `a` to `z` at byte `at`
+    InvalidChar { at: usize, found: char },
+    /// No password of the same length after the current one is valid
+    Exhausted,
 }
 
-fn next(input: &str) -> Option<String> {
-    if input.is_empty() {
-        return None;
+impl Display for PasswordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PasswordError::InvalidChar { at, found } => {
+                write!(f, "'{found}' at {at} is not a lowercase letter")
+            }
+            PasswordError::Exhausted => write!(f, "no valid password follows"),
+        }
     }
+}
 
-    let first = input.chars().next().unwrap();
+impl std::error::Error for PasswordError {}
 
-    if has_forbidden_chars(&first.to_string()) {
-        return Some(
-            String::from(std::char::from_u32(first as u32 + 1).unwrap())
-                + &input[1..].chars().map(|_| 'a').collect::<String>(),
-        );
+/// The valid passwords after a given one, in order. Passwords keep their width and are counted
+/// in base 26 with `a` as 0, going past forbidden letters without trying them.
+#[derive(Debug, Clone)]
+pub struct PasswordIter {
+    password: Vec<u8>,
+    rules: Rules,
+    done: bool,
+}
+
+impl PasswordIter {
+    pub fn new(current: &str, rules: Rules) -> Result<Self, PasswordError> {
+        if let Some((at, found)) = current
+            .char_indices()
+            .find(|(_, c)| !c.is_ascii_lowercase())
+        {
+            return Err(PasswordError::InvalidChar { at, found });
+        }
+
+        Ok(PasswordIter {
+            password: current.as_bytes().to_vec(),
+            rules,
+            done: !rules.can_be_met(current.len()),
+        })
     }
 
-    let result = if let Some(s) = next(&input[1..]) {
-        String::from(first) + &s
-    } else if first == 'z' {
-        return None;
-    } else {
-        String::from(std::char::from_u32(first as u32 + 1).unwrap())
-            + &input[1..].chars().map(|_| 'a').collect::<String>()
-    };
+    /// Moves to the smallest password after the current one without forbidden letters, or
+    /// returns false when there is none of the same width
+    fn increment(&mut self) -> bool {
+        let Some(lowest) = self.rules.allowed_from(b'a') else {
+            return false;
+        };
 
-    Some(result)
+        // A forbidden letter, only ever in the starting password, is bumped rather than the last
+        // letter, and everything after it cannot be smaller
+        let end = match self
+            .password
+            .iter()
+            .position(|&letter| self.rules.is_forbidden(letter))
+        {
+            Some(at) => at + 1,
+            None => self.password.len(),
+        };
+
+        let mut carry = true;
+        for i in (0..end).rev() {
+            let letter = self.password[i];
+            let bumped = if self.rules.is_forbidden(letter) {
+                self.rules.allowed_from(letter)
+            } else {
+                letter
+                    .checked_add(1)
+                    .filter(|next| *next <= b'z')
+                    .and_then(|next| self.rules.allowed_from(next))
+            };
+            match bumped {
+                Some(bumped) => {
+                    self.password[i] = bumped;
+                    carry = false;
+                    break;
+                }
+                None => self.password[i] = lowest,
+            }
+        }
+        self.password[end..].fill(lowest);
+
+        !carry
+    }
 }
 
-#[aoc(day11, part1)]
-fn part1(input: &str) -> String {
-    let mut result = input.to_string();
+impl Iterator for PasswordIter {
+    type Item = String;
 
-    while !is_valid(&result) {
-        result = next(&result).unwrap();
+    fn next(&mut self) -> Option<String> {
+        while !self.done {
+            if !self.increment() {
+                self.done = true;
+            } else if self.rules.is_valid(&self.password) {
+                return Some(String::from_utf8(self.password.clone()).unwrap());
+            }
+        }
+        None
     }
+}
 
-    result.to_owned()
+#[aoc(day11, part1)]
+fn part1(input: &str) -> Result<String, PasswordError> {
+    PasswordIter::new(input, Rules::default())?
+        .next()
+        .ok_or(PasswordError::Exhausted)
 }
 
 #[aoc(day11, part2)]
-fn part2(input: &str) -> String {
-    part1(&next(&part1(input)).unwrap())
+fn part2(input: &str) -> Result<String, PasswordError> {
+    PasswordIter::new(input, Rules::default())?
+        .nth(1)
+        .ok_or(PasswordError::Exhausted)
 }
 
 #[cfg(test)]
@@ -88,39 +227,86 @@ mod tests {
 
     #[test]
     fn test_has_increasing() {
-        assert!(has_increasing("abc"));
-        assert!(has_increasing("cde"));
-        assert!(has_increasing("xyz"));
-        assert!(!has_increasing("abd"));
-        assert!(!has_increasing("cba"));
+        let rules = Rules::default();
+        assert!(rules.has_straight(b"abc"));
+        assert!(rules.has_straight(b"cde"));
+        assert!(rules.has_straight(b"xyz"));
+        assert!(!rules.has_straight(b"abd"));
+        assert!(!rules.has_straight(b"cba"));
+        assert!(rules.straight(4).has_straight(b"xabcd"));
+        assert!(!rules.straight(4).has_straight(b"abcxbcd"));
     }
 
     #[test]
     fn test_no_forbidden_chars() {
-        assert!(has_forbidden_chars("abc"));
-        assert!(has_forbidden_chars("abcdefghj"));
-        assert!(!has_forbidden_chars("aei"));
-        assert!(!has_forbidden_chars("aeo"));
-        assert!(!has_forbidden_chars("l"));
+        let rules = Rules::default();
+        assert!(!rules.has_forbidden(b"abc"));
+        assert!(!rules.has_forbidden(b"abcdefghj"));
+        assert!(rules.has_forbidden(b"aei"));
+        assert!(rules.has_forbidden(b"aeo"));
+        assert!(rules.has_forbidden(b"l"));
+        assert!(!rules.forbid("xyz").has_forbidden(b"iol"));
     }
 
     #[test]
     fn test_different_pairs() {
-        assert!(different_pairs("aabb"));
-        assert!(!different_pairs("aaabc"));
-        assert!(different_pairs("abccdefgg"));
-        assert!(!different_pairs("abacadafgg"));
-        assert!(different_pairs("bacadafftinioo"));
+        let rules = Rules::default();
+        assert!(rules.has_pairs(b"aabb"));
+        assert!(!rules.has_pairs(b"aaabc"));
+        assert!(rules.has_pairs(b"abccdefgg"));
+        assert!(!rules.has_pairs(b"abacadafgg"));
+        assert!(rules.has_pairs(b"bacadafftinioo"));
+        assert!(!rules.pairs(3).has_pairs(b"bacadafftinioo"));
     }
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1("abcdefgh"), "abcdffaa");
-        assert_eq!(part1("ghijklmn"), "ghjaabcc");
+        assert_eq!(part1("abcdefgh"), Ok("abcdffaa".to_string()));
+        assert_eq!(part1("ghijklmn"), Ok("ghjaabcc".to_string()));
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2("<EXAMPLE>"), "<RESULT>");
+        assert_eq!(part2("abcdefgh"), Ok("abcdffbb".to_string()));
+    }
+
+    #[test]
+    fn test_against_counting() {
+        // Every password of width 4 in turn, checking each the slow way
+        let rules = Rules::default().straight(2).pairs(1);
+        let all: Vec<String> = (0..26u32.pow(4))
+            .map(|n| {
+                (0..4)
+                    .rev()
+                    .map(|place| char::from(b'a' + (n / 26u32.pow(place) % 26) as u8))
+                    .collect()
+            })
+            .filter(|password: &String| rules.is_valid(password.as_bytes()))
+            .collect();
+
+        for start in ["aaaa", "hizz", "kzzz", "lmno", "zyxw"] {
+            let expected = all.iter().filter(|password| password.as_str() > start);
+            let found = PasswordIter::new(start, rules).unwrap();
+            assert!(found.eq(expected.cloned()), "after {start}");
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            part1("abcDef").unwrap_err(),
+            PasswordError::InvalidChar { at: 3, found: 'D' }
+        );
+        assert_eq!(part1("zzzzzzzz"), Err(PasswordError::Exhausted));
+        assert_eq!(part1("abc"), Err(PasswordError::Exhausted));
+        assert_eq!(
+            PasswordIter::new(
+                "aaaa",
+                Rules::default().forbid("abcdefghijklmnopqrstuvwxyz")
+            )
+            .unwrap()
+            .next(),
+            None
+        );
     }
 }
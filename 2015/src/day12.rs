@@ -1,84 +1,64 @@
+use std::{error::Error, fmt::Display};
+
 use aoc_runner_derive::aoc;
 
-#[aoc(day12, part1)]
-fn part1(input: &str) -> i32 {
-    let mut current = 0;
-    let chars: Vec<char> = input.chars().collect();
-    let mut numbers: Vec<i32> = vec![];
-    while current < chars.len() {
-        if chars[current].is_ascii_digit() || chars[current] == '-' {
-            let mut number = current + 1;
-            while number < chars.len() && chars[number].is_ascii_digit() {
-                number += 1;
-            }
-            if let Ok(n) = chars[current..number]
-                .iter()
-                .collect::<String>()
-                .parse::<i32>()
-            {
-                numbers.push(n)
-            }
+use crate::json::{self, JsonError, Numbers, SumError, Value};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocumentError {
+    Json(JsonError),
+    Sum(SumError),
+}
 
-            current = number;
-        } else {
-            current += 1
+impl Display for DocumentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DocumentError::Json(e) => e.fmt(f),
+            DocumentError::Sum(e) => e.fmt(f),
         }
     }
-    numbers.iter().sum()
 }
 
-#[aoc(day12, part2)]
-fn part2(input: &str) -> i32 {
-    let mut current = 0;
-    let chars: Vec<char> = input.chars().collect();
-    let mut numbers: Vec<i32> = vec![];
-    while current < chars.len() {
-        if chars[current] == '{' {
-            let mut next = current + 1;
-            let mut red_found = false;
-            let mut nested = 1;
-            while nested != 0 {
-                match chars[next] {
-                    '{' => nested += 1,
-                    '}' => nested -= 1,
-                    ':' => {
-                        if nested == 1
-                            && next < chars.len() - 5
-                            && chars[next..next + 5].iter().collect::<String>() == ":\"red"
-                        {
-                            red_found = true;
-                            next += 4
-                        }
-                    }
-                    _ => {}
-                };
-                next += 1;
-            }
-            if red_found {
-                current = next;
-                continue;
-            }
-        };
+impl Error for DocumentError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DocumentError::Json(e) => Some(e),
+            DocumentError::Sum(e) => Some(e),
+        }
+    }
+}
 
-        if chars[current].is_ascii_digit() || chars[current] == '-' {
-            let mut number = current + 1;
-            while number < chars.len() && chars[number].is_ascii_digit() {
-                number += 1;
-            }
-            if let Ok(n) = chars[current..number]
-                .iter()
-                .collect::<String>()
-                .parse::<i32>()
-            {
-                numbers.push(n)
-            }
+impl From<JsonError> for DocumentError {
+    fn from(e: JsonError) -> Self {
+        DocumentError::Json(e)
+    }
+}
 
-            current = number;
-        } else {
-            current += 1
-        }
+impl From<SumError> for DocumentError {
+    fn from(e: SumError) -> Self {
+        DocumentError::Sum(e)
     }
-    numbers.iter().sum()
+}
+
+/// The numbers of the document, leaving out objects with a `"red"` value if `skip_red`
+pub fn numbers(input: &str, skip_red: bool) -> Result<Numbers, JsonError> {
+    let document = json::parse(input)?;
+    let mut numbers = Numbers::default();
+    if skip_red {
+        numbers = numbers.skip_objects_with(Value::String("red".to_string()));
+    }
+    json::walk(&document, &mut numbers);
+    Ok(numbers)
+}
+
+#[aoc(day12, part1)]
+fn part1(input: &str) -> Result<i64, DocumentError> {
+    Ok(numbers(input, false)?.integer_sum()?)
+}
+
+#[aoc(day12, part2)]
+fn part2(input: &str) -> Result<i64, DocumentError> {
+    Ok(numbers(input, true)?.integer_sum()?)
 }
 
 #[cfg(test)]
@@ -87,20 +67,36 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1("[1,2,3]"), 6);
-        assert_eq!(part1("{\"a\":2,\"b\":4"), 6);
-        assert_eq!(part1("[[[3]]]"), 3);
-        assert_eq!(part1("{\"a\":{\"b\":4},\"c\":-1}"), 3);
-        assert_eq!(part1("{\"a\":[-1,1]}"), 0);
-        assert_eq!(part1("[]"), 0);
-        assert_eq!(part1("{}"), 0);
+        assert_eq!(part1("[1,2,3]"), Ok(6));
+        assert_eq!(part1("{\"a\":2,\"b\":4}"), Ok(6));
+        assert_eq!(part1("[[[3]]]"), Ok(3));
+        assert_eq!(part1("{\"a\":{\"b\":4},\"c\":-1}"), Ok(3));
+        assert_eq!(part1("{\"a\":[-1,1]}"), Ok(0));
+        assert_eq!(part1("[]"), Ok(0));
+        assert_eq!(part1("{}"), Ok(0));
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2("[1,{\"c\":\"red\",\"b\":2},3]"), 4);
-        assert_eq!(part2("[1,2,3]"), 6);
-        assert_eq!(part2("{\"d\":\"red\",\"e\":[1,2,3,4],\"f\":5}"), 0);
-        assert_eq!(part2("[1,\"red\",5]"), 6);
+        assert_eq!(part2("[1,{\"c\":\"red\",\"b\":2},3]"), Ok(4));
+        assert_eq!(part2("[1,2,3]"), Ok(6));
+        assert_eq!(part2("{\"d\":\"red\",\"e\":[1,2,3,4],\"f\":5}"), Ok(0));
+        assert_eq!(part2("[1,\"red\",5]"), Ok(6));
+    }
+
+    #[test]
+    fn test_tricky_strings() {
+        // Braces and quotes inside strings are not structure, and a key of "red" is not a value
+        let input =
+            r#"[{"}": "{\"red", "a": 1}, {"red": 2, "b": "x:\"red"}, {"c": ["red"], "d": 3}]"#;
+        assert_eq!(part2(input), Ok(6));
+        let Err(DocumentError::Json(error)) = part1("{\"a\":2,\"b\":4") else {
+            panic!("an unclosed object should not parse")
+        };
+        assert_eq!((error.at.line, error.at.column), (1, 13));
+        assert!(matches!(
+            part1("[1, 2.5]"),
+            Err(DocumentError::Sum(SumError::NotInteger(_)))
+        ));
     }
 }
@@ -1,90 +1,63 @@
-use std::collections::{HashMap, HashSet};
-
+use advent_of_code_common::parse::{lines, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 
-type Data = (HashMap<(String, String), i32>, HashSet<String>);
+use crate::tsp::{solve, Goal, Graph, RouteError, Shape};
 
 #[aoc_generator(day13)]
-fn parse(input: &str) -> Data {
-    let mut happiness = HashMap::new();
-    let mut people = HashSet::new();
-    let re = regex::Regex::new(r"(?P<from>\w+) would (?P<gainlose>gain|lose) (?P<points>\d+) happiness units by sitting next to (?P<to>\w+)").unwrap();
-
-    for line in input.lines() {
-        let data = re.captures(line).unwrap();
-        let points = data["points"].parse::<i32>().unwrap();
-        let value = if &data["gainlose"] == "gain" {
-            points
-        } else {
-            -points
-        };
-        happiness.insert((data["from"].to_owned(), data["to"].to_owned()), value);
-        people.insert(data["from"].to_owned());
-    }
+fn parse(input: &str) -> Result<Graph, ParseError> {
+    let mut happiness = Graph::default();
 
-    (happiness, people)
-}
+    for mut line in lines(input) {
+        let from = line.word()?;
+        line.literal(" would ")?;
+        let sign = line.one_of(&[("gain ", 1), ("lose ", -1)])?;
+        let points: i64 = line.number()?;
+        line.literal(" happiness units by sitting next to ")?;
+        let to = line.word()?;
+        line.literal(".")?;
+        line.end()?;
 
-fn maximize_happiness(
-    input: &Data,
-    visited: &mut Vec<String>,
-    pending: &HashSet<String>,
-    acc: i32,
-) -> i32 {
-    if pending.len() == 1 {
-        let p = pending.iter().next().unwrap().to_owned();
-        let first = visited.first().unwrap().to_owned();
-        let last = visited.last().unwrap().to_owned();
-        let value = acc
-            + input.0[&(p.clone(), first.clone())]
-            + input.0[&(first, p.clone())]
-            + input.0[&(last.clone(), p.clone())]
-            + input.0[&(p.clone(), last)];
-        return value;
+        happiness.set(from, to, sign * points);
     }
 
-    let from = visited.last().unwrap().to_owned();
-    let mut max = i32::MIN;
-    let mut process = pending.clone();
+    Ok(happiness)
+}
 
-    for person in process.clone() {
-        process.remove(&person);
-        visited.push(person.to_owned());
-        let value = maximize_happiness(
-            input,
-            visited,
-            &process,
-            acc + input.0[&(from.clone(), person.to_owned())]
-                + input.0[&(person.to_owned(), from.clone())],
-        );
-        visited.pop();
-        process.insert(person.to_owned());
+/// What seating each pair side by side is worth, both of their feelings summed
+fn neighbours(happiness: &Graph) -> Result<Graph, RouteError> {
+    let people: Vec<&str> = happiness.names().collect();
+    let feeling = |from: &str, to: &str| {
+        happiness
+            .weight(from, to)
+            .ok_or_else(|| RouteError::MissingEdge {
+                from: from.to_string(),
+                to: to.to_string(),
+            })
+    };
 
-        if value > max {
-            max = value
+    let mut pairs = Graph::default();
+    for &a in &people {
+        pairs.node(a);
+        for &b in people.iter().filter(|&&b| b != a) {
+            pairs.set(a, b, feeling(a, b)? + feeling(b, a)?);
         }
     }
-    max
+    Ok(pairs)
 }
 
 #[aoc(day13, part1)]
-fn part1(input: &Data) -> i32 {
-    let mut list: Vec<String> = input.1.clone().into_iter().collect();
-    let first = list.pop().unwrap();
-    let pending = HashSet::from_iter(list);
-    let mut visited = vec![first];
-
-    maximize_happiness(input, &mut visited, &pending, 0)
+fn part1(input: &Graph) -> Result<i64, RouteError> {
+    Ok(solve(&neighbours(input)?, Shape::Cycle, Goal::Max)?.cost)
 }
 
 #[aoc(day13, part2)]
-fn part2(input: &Data) -> i32 {
+fn part2(input: &Graph) -> Result<i64, RouteError> {
     let mut input = input.clone();
-    for person in input.1.clone() {
-        input.0.insert(("Me".to_string(), person.clone()), 0);
-        input.0.insert((person, "Me".to_string()), 0);
+    let people: Vec<String> = input.names().map(str::to_string).collect();
+    for person in people {
+        input.set("Me", &person, 0);
+        input.set(&person, "Me", 0);
     }
-    input.1.insert("Me".to_string());
     part1(&input)
 }
 
@@ -95,8 +68,9 @@ mod tests {
     #[test]
     fn part1_example() {
         assert_eq!(
-            part1(&parse(
-                r#"Alice would gain 54 happiness units by sitting next to Bob.
+            part1(
+                &parse(
+                    r#"Alice would gain 54 happiness units by sitting next to Bob.
                 Alice would lose 79 happiness units by sitting next to Carol.
                 Alice would lose 2 happiness units by sitting next to David.
                 Bob would gain 83 happiness units by sitting next to Alice.
@@ -108,16 +82,19 @@ mod tests {
                 David would gain 46 happiness units by sitting next to Alice.
                 David would lose 7 happiness units by sitting next to Bob.
                 David would gain 41 happiness units by sitting next to Carol."#
-            )),
-            330
+                )
+                .unwrap()
+            ),
+            Ok(330)
         );
     }
 
     #[test]
     fn part2_example() {
         assert_eq!(
-            part2(&parse(
-                r#"Alice would gain 54 happiness units by sitting next to Bob.
+            part2(
+                &parse(
+                    r#"Alice would gain 54 happiness units by sitting next to Bob.
                 Alice would lose 79 happiness units by sitting next to Carol.
                 Alice would lose 2 happiness units by sitting next to David.
                 Bob would gain 83 happiness units by sitting next to Alice.
@@ -129,8 +106,48 @@ mod tests {
                 David would gain 46 happiness units by sitting next to Alice.
                 David would lose 7 happiness units by sitting next to Bob.
                 David would gain 41 happiness units by sitting next to Carol."#
-            )),
-            286
+                )
+                .unwrap()
+            ),
+            Ok(286)
+        );
+    }
+
+    #[test]
+    fn test_seating() {
+        let happiness = parse(
+            "Alice would gain 54 happiness units by sitting next to Bob.
+            Bob would gain 83 happiness units by sitting next to Alice.
+            Alice would lose 79 happiness units by sitting next to Carol.
+            Carol would lose 62 happiness units by sitting next to Alice.
+            Bob would lose 7 happiness units by sitting next to Carol.",
+        )
+        .unwrap();
+        assert_eq!(
+            part1(&happiness).unwrap_err().to_string(),
+            "no edge from Carol to Bob"
         );
+
+        let pairs = neighbours(
+            &parse(
+                "Alice would gain 5 happiness units by sitting next to Bob.
+                Bob would lose 2 happiness units by sitting next to Alice.",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let seating = solve(&pairs, Shape::Cycle, Goal::Max).unwrap();
+        assert_eq!(seating.order, ["Alice", "Bob"]);
+        assert_eq!(seating.cost, 6);
+    }
+
+    #[test]
+    fn parse_error() {
+        let error =
+            parse("Alice would gain 54 happiness units by sitting next to Bob.\nBob would win 83")
+                .unwrap_err();
+        assert_eq!((error.line, error.column), (2, 11));
+        assert_eq!(error.expected, "one of 'gain ', 'lose '");
+        assert_eq!(error.found(), "win");
     }
 }
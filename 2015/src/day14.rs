@@ -1,9 +1,16 @@
-use std::collections::HashMap;
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    io::{self, Write},
+};
 
+use advent_of_code_common::parse::{lines, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 
-struct Reindeer {
-    name: String,
+/// Flies at `speed` km/s for `duration` seconds, rests `rest` seconds, and again
+#[derive(Debug)]
+pub struct Reindeer {
+    pub name: String,
     speed: u32,
     duration: u32,
     rest: u32,
@@ -18,98 +25,310 @@ impl Reindeer {
             rest,
         }
     }
+}
+
+/// A reindeer's last change between flying and resting
+#[derive(Debug, Clone, Copy)]
+struct Phase {
+    flying: bool,
+    since: u32,
+    distance: u32,
+}
+
+/// The standings at the end of one second of the race, reindeer in the order given
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Second {
+    pub time: u32,
+    pub distances: Vec<u32>,
+    /// Every reindeer furthest ahead, more than one on a tie; each of them scored a point
+    pub leaders: Vec<usize>,
+    pub points: Vec<u32>,
+    /// Whether the leaders are not those of the second before; never on the first second
+    pub lead_changed: bool,
+}
 
-    fn distance_after(&self, time: u32) -> u32 {
-        let amount = time / (self.duration + self.rest);
-        let remainder = time % (self.duration + self.rest);
+/// A race of `duration` seconds. Reindeer only change speed when they start or stop flying, so
+/// the race jumps from one of these events to the next, and distances in between follow from the
+/// last one. Iterating goes a second at a time, scoring the leaders of each.
+#[derive(Debug)]
+pub struct Race<'a> {
+    reindeer: &'a [Reindeer],
+    duration: u32,
+    time: u32,
+    phases: Vec<Phase>,
+    /// When each reindeer next starts or stops flying, soonest first
+    events: BinaryHeap<Reverse<(u32, usize)>>,
+    points: Vec<u32>,
+    leaders: Vec<usize>,
+}
+
+impl<'a> Race<'a> {
+    pub fn new(reindeer: &'a [Reindeer], duration: u32) -> Self {
+        let mut events = BinaryHeap::new();
+        let phases = reindeer
+            .iter()
+            .enumerate()
+            .map(|(i, r)| {
+                // Those that never fly or never rest have no events
+                if r.duration > 0 && r.rest > 0 {
+                    events.push(Reverse((r.duration, i)));
+                }
+                Phase {
+                    flying: r.duration > 0,
+                    since: 0,
+                    distance: 0,
+                }
+            })
+            .collect();
 
-        let extra = if remainder > self.duration {
-            self.duration * self.speed
+        Race {
+            reindeer,
+            duration,
+            time: 0,
+            phases,
+            events,
+            points: vec![0; reindeer.len()],
+            leaders: vec![],
+        }
+    }
+
+    fn distance(&self, i: usize) -> u32 {
+        let phase = &self.phases[i];
+        if phase.flying {
+            phase.distance + self.reindeer[i].speed * (self.time - phase.since)
         } else {
-            remainder * self.speed
-        };
+            phase.distance
+        }
+    }
+
+    /// Moves the clock to `time`, going through the events up to it but scoring nothing
+    fn advance_to(&mut self, time: u32) {
+        while let Some(&Reverse((at, i))) = self.events.peek() {
+            if at > time {
+                break;
+            }
+            self.events.pop();
+            self.time = at;
 
-        self.speed * self.duration * amount + extra
+            let reindeer = &self.reindeer[i];
+            let flying = !self.phases[i].flying;
+            self.phases[i] = Phase {
+                flying,
+                since: at,
+                distance: self.distance(i),
+            };
+            let length = if flying {
+                reindeer.duration
+            } else {
+                reindeer.rest
+            };
+            self.events.push(Reverse((at + length, i)));
+        }
+        self.time = time;
+    }
+
+    /// The distances at the end of the race, jumping straight there
+    pub fn finish(mut self) -> Vec<u32> {
+        self.advance_to(self.duration);
+        (0..self.reindeer.len()).map(|i| self.distance(i)).collect()
+    }
+
+    /// Writes the remaining seconds as CSV: the second, the leaders, whether the lead changed,
+    /// then each reindeer's distance and each one's points
+    pub fn write_csv(self, mut writer: impl Write) -> io::Result<()> {
+        let names: Vec<&str> = self.reindeer.iter().map(|r| r.name.as_str()).collect();
+        write!(writer, "second,leaders,lead_change")?;
+        for name in &names {
+            write!(writer, ",{name} km")?;
+        }
+        for name in &names {
+            write!(writer, ",{name} points")?;
+        }
+        writeln!(writer)?;
+
+        for second in self {
+            let leaders: Vec<&str> = second.leaders.iter().map(|&i| names[i]).collect();
+            write!(
+                writer,
+                "{},{},{}",
+                second.time,
+                leaders.join(";"),
+                second.lead_changed
+            )?;
+            for value in second.distances.iter().chain(&second.points) {
+                write!(writer, ",{value}")?;
+            }
+            writeln!(writer)?;
+        }
+        writer.flush()
+    }
+}
+
+impl Iterator for Race<'_> {
+    type Item = Second;
+
+    fn next(&mut self) -> Option<Second> {
+        if self.time >= self.duration {
+            return None;
+        }
+        self.advance_to(self.time + 1);
+
+        let distances: Vec<u32> = (0..self.reindeer.len()).map(|i| self.distance(i)).collect();
+        let furthest = distances.iter().max().copied().unwrap_or(0);
+        let leaders: Vec<usize> = (0..distances.len())
+            .filter(|&i| distances[i] == furthest)
+            .collect();
+        for &i in &leaders {
+            self.points[i] += 1;
+        }
+        let lead_changed = self.time > 1 && leaders != self.leaders;
+        self.leaders.clone_from(&leaders);
+
+        Some(Second {
+            time: self.time,
+            distances,
+            leaders,
+            points: self.points.clone(),
+            lead_changed,
+        })
     }
 }
 
 #[aoc_generator(day14)]
-fn parse(input: &str) -> Vec<Reindeer> {
+pub fn parse(input: &str) -> Result<Vec<Reindeer>, ParseError> {
     let mut reindeers = vec![];
-    let re = regex::Regex::new(r"(?P<reindeer>\w+) can fly (?P<speed>\d+) km/s for (?P<duration>\d+) seconds, but then must rest for (?P<rest>\d+) seconds").unwrap();
-
-    for line in input.lines() {
-        let data = re.captures(line).unwrap();
-        reindeers.push(Reindeer::new(
-            data["reindeer"].to_string(),
-            data["speed"].to_string().parse().unwrap(),
-            data["duration"].to_string().parse().unwrap(),
-            data["rest"].to_string().parse().unwrap(),
-        ))
+
+    for mut line in lines(input) {
+        let name = line.word()?.to_string();
+        line.literal(" can fly ")?;
+        let speed = line.number()?;
+        line.literal(" km/s for ")?;
+        let duration = line.number()?;
+        line.literal(" seconds, but then must rest for ")?;
+        let rest = line.number()?;
+        line.literal(" seconds.")?;
+        line.end()?;
+
+        reindeers.push(Reindeer::new(name, speed, duration, rest))
     }
-    reindeers
+    Ok(reindeers)
 }
 
 #[aoc(day14, part1)]
 fn part1(reindeers: &[Reindeer]) -> u32 {
-    reindeers
-        .iter()
-        .map(|r| r.distance_after(2503))
+    Race::new(reindeers, 2503)
+        .finish()
+        .into_iter()
         .max()
         .unwrap()
 }
 
 #[aoc(day14, part2)]
 fn part2(reindeers: &[Reindeer]) -> u32 {
-    let mut standings = HashMap::<String, u32>::new();
-
-    for time in 1..2504 {
-        let race: Vec<(String, u32)> = reindeers
-            .iter()
-            .map(|r| (r.name.clone(), r.distance_after(time)))
-            .collect();
-        let (_, max_distance) = race
-            .iter()
-            .max_by(|(_, v1), (_, v2)| v1.partial_cmp(v2).unwrap())
-            .unwrap()
-            .to_owned();
-        let ties: Vec<String> = race
-            .into_iter()
-            .filter(|(_, v)| *v == max_distance)
-            .map(|(r, _)| r)
-            .collect();
-
-        for winner in ties {
-            standings.entry(winner).and_modify(|v| *v += 1).or_insert(1);
-        }
-    }
-
-    standings.values().max().unwrap().to_owned()
+    let last = Race::new(reindeers, 2503).last().unwrap();
+    last.points.into_iter().max().unwrap()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = r#"Comet can fly 14 km/s for 10 seconds, but then must rest for 127 seconds.
+                Dancer can fly 16 km/s for 11 seconds, but then must rest for 162 seconds."#;
+
+    /// The distance in closed form, the way the race used to be run each second
+    fn distance_after(reindeer: &Reindeer, time: u32) -> u32 {
+        let cycle = reindeer.duration + reindeer.rest;
+        if cycle == 0 {
+            return 0;
+        }
+        let flown = time / cycle * reindeer.duration + (time % cycle).min(reindeer.duration);
+        flown * reindeer.speed
+    }
+
     #[test]
     fn part1_example() {
-        assert_eq!(
-            part1(&parse(
-                r#"Comet can fly 14 km/s for 10 seconds, but then must rest for 127 seconds.
-                Dancer can fly 16 km/s for 11 seconds, but then must rest for 162 seconds."#
-            )),
-            2660
-        );
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 2660);
     }
 
     #[test]
     fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 1564);
+    }
+
+    #[test]
+    fn parse_error() {
+        let error =
+            parse("Comet can fly 14 km/s for 10 seconds, but then must rest for 127").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 65));
+        assert_eq!(error.expected, "'seconds.'");
+    }
+
+    #[test]
+    fn test_against_closed_form() {
+        let mut reindeer = parse(EXAMPLE).unwrap();
+        for (name, speed, duration, rest) in [("a", 3, 1, 1), ("b", 5, 0, 4), ("c", 2, 7, 0)] {
+            reindeer.push(Reindeer::new(name.to_string(), speed, duration, rest));
+        }
+
+        let mut seconds = 0;
+        for second in Race::new(&reindeer, 400) {
+            seconds += 1;
+            assert_eq!(second.time, seconds);
+            for (r, distance) in reindeer.iter().zip(&second.distances) {
+                assert_eq!(
+                    *distance,
+                    distance_after(r, seconds),
+                    "{} at {seconds}",
+                    r.name
+                );
+            }
+        }
+        assert_eq!(seconds, 400);
+
+        for time in [0, 1, 10, 11, 137, 1000, 2503] {
+            let expected: Vec<u32> = reindeer.iter().map(|r| distance_after(r, time)).collect();
+            assert_eq!(Race::new(&reindeer, time).finish(), expected);
+        }
+    }
+
+    #[test]
+    fn test_timeline() {
+        let reindeer = parse(EXAMPLE).unwrap();
+        let timeline: Vec<Second> = Race::new(&reindeer, 1000).collect();
+
+        assert_eq!(timeline[0].leaders, [1]);
+        let changes: Vec<(u32, &[usize])> = timeline
+            .iter()
+            .filter(|second| second.lead_changed)
+            .map(|second| (second.time, second.leaders.as_slice()))
+            .collect();
+        assert_eq!(changes[0], (140, [0].as_slice()));
+        assert_eq!(timeline[999].points, [312, 689]);
+
+        let twins = parse(
+            "A can fly 5 km/s for 2 seconds, but then must rest for 3 seconds.
+             B can fly 5 km/s for 2 seconds, but then must rest for 3 seconds.",
+        )
+        .unwrap();
+        let last = Race::new(&twins, 10).last().unwrap();
+        assert_eq!((last.leaders, last.points), (vec![0, 1], vec![10, 10]));
+    }
+
+    #[test]
+    fn test_csv() {
+        let reindeer = parse(EXAMPLE).unwrap();
+        let mut csv = vec![];
+        Race::new(&reindeer, 140).write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 141);
         assert_eq!(
-            part2(&parse(
-                r#"Comet can fly 14 km/s for 10 seconds, but then must rest for 127 seconds.
-                Dancer can fly 16 km/s for 11 seconds, but then must rest for 162 seconds."#
-            )),
-            1564
+            lines[0],
+            "second,leaders,lead_change,Comet km,Dancer km,Comet points,Dancer points"
         );
+        assert_eq!(lines[1], "1,Dancer,false,14,16,0,1");
+        assert_eq!(lines[140], "140,Comet,true,182,176,1,139");
     }
 }
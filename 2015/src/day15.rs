@@ -1,13 +1,9 @@
-use std::{
-    iter::Sum,
-    ops::{Add, Mul},
-};
-
+use advent_of_code_common::parse::{lines, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 
 #[derive(Debug, Clone)]
-struct Ingredient {
-    name: String,
+pub struct Ingredient {
+    pub name: String,
     capacity: i64,
     durability: i64,
     flavor: i64,
@@ -15,129 +11,181 @@ struct Ingredient {
     calories: i64,
 }
 
-impl Mul<i64> for Ingredient {
-    type Output = Self;
-
-    fn mul(self, rhs: i64) -> Self::Output {
-        Ingredient {
-            name: self.name + &format!("*{rhs}"),
-            capacity: self.capacity * rhs,
-            durability: self.durability * rhs,
-            flavor: self.flavor * rhs,
-            texture: self.texture * rhs,
-            calories: self.calories * rhs,
-        }
+impl Ingredient {
+    /// The properties that make up the score
+    fn properties(&self) -> [i64; 4] {
+        [self.capacity, self.durability, self.flavor, self.texture]
     }
 }
 
-impl Add for Ingredient {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Ingredient {
-            name: self.name + "+" + &rhs.name,
-            capacity: self.capacity + rhs.capacity,
-            durability: self.durability + rhs.durability,
-            flavor: self.flavor + rhs.flavor,
-            texture: self.texture + rhs.texture,
-            calories: self.calories + rhs.calories,
+/// The best mix found: teaspoons of each ingredient, in the order given, and its score
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recipe {
+    pub amounts: Vec<i64>,
+    pub score: i64,
+}
+
+/// What is left to choose from ingredient `k` on: the most each property and the least and most
+/// calories a single teaspoon of any of them brings
+#[derive(Debug, Clone, Copy)]
+struct Suffix {
+    properties: [i64; 4],
+    calories: (i64, i64),
+}
+
+struct Search<'a> {
+    ingredients: &'a [Ingredient],
+    suffixes: Vec<Suffix>,
+    calories: Option<i64>,
+    amounts: Vec<i64>,
+    best: Option<Recipe>,
+}
+
+impl Search<'_> {
+    /// Whether spreading `teaspoons` over the ingredients from `k` on, on top of `totals`, could
+    /// still meet the calorie constraint and beat the best recipe so far
+    fn promising(&self, k: usize, teaspoons: i64, totals: &[i64; 5]) -> bool {
+        let suffix = &self.suffixes[k];
+        if let Some(calories) = self.calories {
+            let (least, most) = suffix.calories;
+            if calories < totals[4] + teaspoons * least || calories > totals[4] + teaspoons * most {
+                return false;
+            }
         }
+
+        let Some(best) = &self.best else {
+            return true;
+        };
+        // A property that cannot end up positive makes every score from here 0
+        let bound = (0..4)
+            .map(|p| (totals[p] + teaspoons * suffix.properties[p]).max(0))
+            .fold(1i64, i64::saturating_mul);
+        bound > best.score
     }
-}
 
-impl Sum for Ingredient {
-    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
-        iter.fold(Ingredient::new(), |acc, i| acc + i)
+    fn go(&mut self, k: usize, teaspoons: i64, totals: [i64; 5]) {
+        let ingredient = &self.ingredients[k];
+        let amounts = if k + 1 == self.ingredients.len() {
+            teaspoons..=teaspoons
+        } else {
+            0..=teaspoons
+        };
+
+        for amount in amounts {
+            let mut totals = totals;
+            for (total, property) in totals.iter_mut().zip(ingredient.properties()) {
+                *total += amount * property;
+            }
+            totals[4] += amount * ingredient.calories;
+            self.amounts.push(amount);
+
+            let rest = teaspoons - amount;
+            if k + 1 < self.ingredients.len() {
+                if self.promising(k + 1, rest, &totals) {
+                    self.go(k + 1, rest, totals);
+                }
+            } else if self.calories.is_none_or(|calories| calories == totals[4]) {
+                let score = totals[..4].iter().map(|&total| total.max(0)).product();
+                if self.best.as_ref().is_none_or(|best| score > best.score) {
+                    self.best = Some(Recipe {
+                        amounts: self.amounts.clone(),
+                        score,
+                    });
+                }
+            }
+
+            self.amounts.pop();
+        }
     }
 }
 
-impl Ingredient {
-    pub fn new() -> Self {
-        Ingredient {
-            name: String::new(),
-            capacity: 0,
-            durability: 0,
-            flavor: 0,
-            texture: 0,
-            calories: 0,
-        }
+/// The best-scoring mix of exactly `teaspoons` teaspoons, with exactly `calories` calories if
+/// given, or `None` when there is no mix at all. Mixes are the compositions of `teaspoons`,
+/// tried in order with branches that cannot do better than the best so far left out.
+pub fn optimise(
+    ingredients: &[Ingredient],
+    teaspoons: i64,
+    calories: Option<i64>,
+) -> Option<Recipe> {
+    if ingredients.is_empty() || teaspoons < 0 {
+        return None;
     }
 
-    pub fn score(&self) -> i64 {
-        if self.capacity < 0 || self.durability < 0 || self.flavor < 0 || self.texture < 0 {
-            return 0;
+    let mut suffixes = vec![
+        Suffix {
+            properties: [i64::MIN; 4],
+            calories: (i64::MAX, i64::MIN),
+        };
+        ingredients.len()
+    ];
+    for k in (0..ingredients.len()).rev() {
+        let mut suffix = suffixes.get(k + 1).copied().unwrap_or(suffixes[k]);
+        for (most, property) in suffix
+            .properties
+            .iter_mut()
+            .zip(ingredients[k].properties())
+        {
+            *most = (*most).max(property);
         }
+        let (least, most) = suffix.calories;
+        suffix.calories = (
+            least.min(ingredients[k].calories),
+            most.max(ingredients[k].calories),
+        );
+        suffixes[k] = suffix;
+    }
 
-        self.capacity * self.durability * self.flavor * self.texture
+    let mut search = Search {
+        ingredients,
+        suffixes,
+        calories,
+        amounts: vec![],
+        best: None,
+    };
+    if search.promising(0, teaspoons, &[0; 5]) {
+        search.go(0, teaspoons, [0; 5]);
     }
+    search.best
 }
 
 #[aoc_generator(day15)]
-fn parse(input: &str) -> Vec<Ingredient> {
+pub fn parse(input: &str) -> Result<Vec<Ingredient>, ParseError> {
     let mut ingredients = vec![];
-    let re = regex::Regex::new(r"(?P<name>\w+): capacity (?P<capacity>[-\d]+), durability (?P<durability>[-\d]+), flavor (?P<flavor>[-\d]+), texture (?P<texture>[-\d]+), calories (?P<calories>[-\d]+)").unwrap();
 
-    for line in input.lines() {
-        let data = re.captures(line).unwrap();
+    for mut line in lines(input) {
+        let name = line.word()?.to_string();
+        line.literal(": capacity ")?;
+        let capacity = line.number()?;
+        line.literal(", durability ")?;
+        let durability = line.number()?;
+        line.literal(", flavor ")?;
+        let flavor = line.number()?;
+        line.literal(", texture ")?;
+        let texture = line.number()?;
+        line.literal(", calories ")?;
+        let calories = line.number()?;
+        line.end()?;
+
         ingredients.push(Ingredient {
-            name: data["name"].to_string(),
-            capacity: data["capacity"].to_string().parse().unwrap(),
-            durability: data["durability"].to_string().parse().unwrap(),
-            flavor: data["flavor"].to_string().parse().unwrap(),
-            texture: data["texture"].to_string().parse().unwrap(),
-            calories: data["calories"].to_string().parse().unwrap(),
+            name,
+            capacity,
+            durability,
+            flavor,
+            texture,
+            calories,
         })
     }
-    ingredients
+    Ok(ingredients)
 }
 
 #[aoc(day15, part1)]
 fn part1(input: &[Ingredient]) -> i64 {
-    let mut max = 0;
-    let mut factors: Vec<i64> = input.iter().map(|_| 0).collect();
-    for x in 1..(1 + 100i64.pow(input.len() as u32)) {
-        for (i, v) in factors.iter_mut().enumerate() {
-            *v = (x / 100i64.pow(i as u32)) % 100;
-        }
-        if factors.iter().sum::<i64>() != 100 {
-            continue;
-        }
-        let result = factors
-            .iter()
-            .enumerate()
-            .map(|(i, v)| input[i].clone() * *v)
-            .sum::<Ingredient>()
-            .score();
-
-        if result > max {
-            max = result
-        }
-    }
-    max
+    optimise(input, 100, None).map_or(0, |recipe| recipe.score)
 }
 
 #[aoc(day15, part2)]
 fn part2(input: &[Ingredient]) -> i64 {
-    let mut max = 0;
-    let mut factors: Vec<i64> = input.iter().map(|_| 0).collect();
-    for x in 1..(1 + 100i64.pow(input.len() as u32)) {
-        for (i, v) in factors.iter_mut().enumerate() {
-            *v = (x / 100i64.pow(i as u32)) % 100;
-        }
-        if factors.iter().sum::<i64>() != 100 {
-            continue;
-        }
-        let result = factors
-            .iter()
-            .enumerate()
-            .map(|(i, v)| input[i].clone() * *v)
-            .sum::<Ingredient>();
-
-        if result.calories == 500 && result.score() > max {
-            max = result.score()
-        }
-    }
-    max
+    optimise(input, 100, Some(500)).map_or(0, |recipe| recipe.score)
 }
 
 #[cfg(test)]
@@ -147,10 +195,13 @@ mod tests {
     #[test]
     fn part1_example() {
         assert_eq!(
-            part1(&parse(
-                r#"Butterscotch: capacity -1, durability -2, flavor 6, texture 3, calories 8
+            part1(
+                &parse(
+                    r#"Butterscotch: capacity -1, durability -2, flavor 6, texture 3, calories 8
                    Cinnamon: capacity 2, durability 3, flavor -2, texture -1, calories 3"#
-            )),
+                )
+                .unwrap()
+            ),
             62842880
         );
     }
@@ -158,11 +209,107 @@ mod tests {
     #[test]
     fn part2_example() {
         assert_eq!(
-            part2(&parse(
-                r#"Butterscotch: capacity -1, durability -2, flavor 6, texture 3, calories 8
+            part2(
+                &parse(
+                    r#"Butterscotch: capacity -1, durability -2, flavor 6, texture 3, calories 8
                    Cinnamon: capacity 2, durability 3, flavor -2, texture -1, calories 3"#
-            )),
+                )
+                .unwrap()
+            ),
             57600000
         );
     }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("Cinnamon: capacity 2, durability x").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 34));
+        assert_eq!(error.expected, "number");
+    }
+
+    #[test]
+    fn test_amounts() {
+        let ingredients = parse(
+            r#"Butterscotch: capacity -1, durability -2, flavor 6, texture 3, calories 8
+               Cinnamon: capacity 2, durability 3, flavor -2, texture -1, calories 3"#,
+        )
+        .unwrap();
+        assert_eq!(
+            optimise(&ingredients, 100, None),
+            Some(Recipe {
+                amounts: vec![44, 56],
+                score: 62842880
+            })
+        );
+        assert_eq!(
+            optimise(&ingredients, 100, Some(500)).unwrap().amounts,
+            [40, 60]
+        );
+        assert_eq!(optimise(&ingredients, 100, Some(299)), None);
+        assert_eq!(optimise(&[], 100, None), None);
+    }
+
+    #[test]
+    fn test_against_brute_force() {
+        for mut seed in [8i64, 17] {
+            let mut random = || {
+                seed = (seed * 1_103_515_245 + 12345) % (1 << 31);
+                seed % 11 - 2
+            };
+            let ingredients: Vec<Ingredient> = (0..4)
+                .map(|i| Ingredient {
+                    name: i.to_string(),
+                    capacity: random(),
+                    durability: random(),
+                    flavor: random(),
+                    texture: random(),
+                    calories: random() + 5,
+                })
+                .collect();
+            let even = ingredients.iter().map(|i| 5 * i.calories).sum();
+
+            for calories in [None, Some(even), Some(0)] {
+                let mut best = None;
+                for a in 0..=20 {
+                    for b in 0..=20 - a {
+                        for c in 0..=20 - a - b {
+                            let amounts = [a, b, c, 20 - a - b - c];
+                            let total = |property: fn(&Ingredient) -> i64| {
+                                ingredients
+                                    .iter()
+                                    .zip(amounts)
+                                    .map(|(ingredient, amount)| property(ingredient) * amount)
+                                    .sum::<i64>()
+                            };
+                            if calories.is_some_and(|calories| total(|i| i.calories) != calories) {
+                                continue;
+                            }
+                            let score = [
+                                total(|i| i.capacity),
+                                total(|i| i.durability),
+                                total(|i| i.flavor),
+                                total(|i| i.texture),
+                            ]
+                            .iter()
+                            .map(|&total| total.max(0))
+                            .product::<i64>();
+                            if best.is_none_or(|best| score > best) {
+                                best = Some(score);
+                            }
+                        }
+                    }
+                }
+
+                let recipe = optimise(&ingredients, 20, calories);
+                assert_eq!(
+                    recipe.as_ref().map(|recipe| recipe.score),
+                    best,
+                    "{calories:?}"
+                );
+                if let Some(recipe) = recipe {
+                    assert_eq!(recipe.amounts.iter().sum::<i64>(), 20);
+                }
+            }
+        }
+    }
 }
@@ -1,152 +1,303 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use advent_of_code_common::parse::{lines, Cursor, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 
-#[derive(Default, Debug)]
-struct Aunt {
-    name: u16,
-    children: i8,
-    cats: i8,
-    samoyeds: i8,
-    pomeranians: i8,
-    akitas: i8,
-    vizslas: i8,
-    goldfish: i8,
-    trees: i8,
-    cars: i8,
-    perfumes: i8,
-}
-
-impl Aunt {
-    fn new(name: u16) -> Aunt {
-        Aunt {
-            name,
-            children: -1,
-            cats: -1,
-            samoyeds: -1,
-            pomeranians: -1,
-            akitas: -1,
-            vizslas: -1,
-            goldfish: -1,
-            trees: -1,
-            cars: -1,
-            perfumes: -1,
+/// What the MFCSAM reads off the gift
+const READING: &str = "children: 3
+cats: 7
+samoyeds: 2
+pomeranians: 3
+akitas: 0
+vizslas: 0
+goldfish: 5
+trees: 3
+cars: 2
+perfumes: 1";
+
+/// An aunt and the compounds remembered about her, whatever they are
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Aunt {
+    pub number: u16,
+    pub compounds: HashMap<String, u32>,
+}
+
+impl TryFrom<Cursor<'_>> for Aunt {
+    type Error = ParseError;
+
+    fn try_from(mut line: Cursor) -> Result<Self, Self::Error> {
+        line.literal("Sue ")?;
+        let number = line.number()?;
+        line.literal(": ")?;
+
+        let mut compounds = HashMap::new();
+        loop {
+            let start = line.clone();
+            let compound = line.word()?;
+            if compounds.contains_key(compound) {
+                return Err(start.error("a compound not given yet"));
+            }
+            line.literal(": ")?;
+            compounds.insert(compound.to_string(), line.number()?);
+
+            if !line.eat(", ") {
+                break;
+            }
         }
+        line.end()?;
+        Ok(Aunt { number, compounds })
     }
+}
 
-    fn part1_eq(&self, other: &Self) -> bool {
-        (self.children == -1 || self.children == other.children)
-            && (self.cats == -1 || self.cats == other.cats)
-            && (self.samoyeds == -1 || self.samoyeds == other.samoyeds)
-            && (self.pomeranians == -1 || self.pomeranians == other.pomeranians)
-            && (self.akitas == -1 || self.akitas == other.akitas)
-            && (self.vizslas == -1 || self.vizslas == other.vizslas)
-            && (self.goldfish == -1 || self.goldfish == other.goldfish)
-            && (self.trees == -1 || self.trees == other.trees)
-            && (self.cars == -1 || self.cars == other.cars)
-            && (self.perfumes == -1 || self.perfumes == other.perfumes)
-    }
-
-    fn part2_eq(&self, other: &Self) -> bool {
-        (self.cats == -1 || self.cats > other.cats)
-            && (self.trees == -1 || self.trees > other.trees)
-            && (self.pomeranians == -1 || self.pomeranians < other.pomeranians)
-            && (self.goldfish == -1 || self.goldfish < other.goldfish)
-            && (self.children == -1 || self.children == other.children)
-            && (self.samoyeds == -1 || self.samoyeds == other.samoyeds)
-            && (self.akitas == -1 || self.akitas == other.akitas)
-            && (self.vizslas == -1 || self.vizslas == other.vizslas)
-            && (self.cars == -1 || self.cars == other.cars)
-            && (self.perfumes == -1 || self.perfumes == other.perfumes)
-    }
-}
-
-impl From<&str> for Aunt {
-    fn from(line: &str) -> Self {
-        let re = regex::Regex::new(r"Sue (?P<name>\d+): (?P<rest>.+)").unwrap();
-        let data = re.captures(line).unwrap();
-        let name = data["name"].to_string().parse().unwrap();
-        let mut aunt = Aunt::new(name);
-        let mut rest = data["rest"].to_string();
-        rest.retain(|c| c != ',' && c != ':');
-        let rest = rest.split_whitespace().collect::<Vec<&str>>();
-        let chunks = rest.chunks(2);
-        for compound in chunks {
-            let compound_value = compound[1].parse().unwrap();
-            match compound[0] {
-                "children" => aunt.children = compound_value,
-                "cats" => aunt.cats = compound_value,
-                "samoyeds" => aunt.samoyeds = compound_value,
-                "pomeranians" => aunt.pomeranians = compound_value,
-                "akitas" => aunt.akitas = compound_value,
-                "vizslas" => aunt.vizslas = compound_value,
-                "goldfish" => aunt.goldfish = compound_value,
-                "trees" => aunt.trees = compound_value,
-                "cars" => aunt.cars = compound_value,
-                "perfumes" => aunt.perfumes = compound_value,
-                _ => unreachable!(),
-            }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparator {
+    Equal(u32),
+    Greater(u32),
+    Less(u32),
+    /// Both ends included
+    Between(u32, u32),
+}
+
+impl Comparator {
+    pub fn accepts(self, value: u32) -> bool {
+        match self {
+            Comparator::Equal(n) => value == n,
+            Comparator::Greater(n) => value > n,
+            Comparator::Less(n) => value < n,
+            Comparator::Between(low, high) => (low..=high).contains(&value),
         }
-        aunt
     }
 }
 
-#[aoc_generator(day16)]
-fn parse(input: &str) -> Vec<Aunt> {
-    let mut aunts = vec![];
-    for line in input.lines() {
-        aunts.push(Aunt::from(line))
+impl Display for Comparator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Comparator::Equal(n) => write!(f, "= {n}"),
+            Comparator::Greater(n) => write!(f, "> {n}"),
+            Comparator::Less(n) => write!(f, "< {n}"),
+            Comparator::Between(low, high) => write!(f, "in {low}..={high}"),
+        }
     }
-    aunts
 }
 
-#[aoc(day16, part1)]
-fn part1(input: &Vec<Aunt>) -> u16 {
-    let my_aunt = Aunt {
-        name: 0,
-        children: 3,
-        cats: 7,
-        samoyeds: 2,
-        pomeranians: 3,
-        akitas: 0,
-        vizslas: 0,
-        goldfish: 5,
-        trees: 3,
-        cars: 2,
-        perfumes: 1,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Constraint {
+    pub compound: String,
+    pub comparator: Comparator,
+}
+
+/// What the ticker tape asks of an aunt, one `compound: condition` after another, separated by
+/// commas or newlines. A condition is a number, `=N`, `>N`, `<N` or the range `A..=B`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tape(pub Vec<Constraint>);
+
+fn constraint(line: &mut Cursor) -> Result<Constraint, ParseError> {
+    let compound = line.word()?.to_string();
+    line.literal(":")?;
+    line.skip_whitespace();
+
+    let comparator = if line.eat(">") {
+        line.skip_whitespace();
+        Comparator::Greater(line.number()?)
+    } else if line.eat("<") {
+        line.skip_whitespace();
+        Comparator::Less(line.number()?)
+    } else {
+        line.eat("=");
+        line.skip_whitespace();
+        let low = line.number()?;
+        if line.eat("..") {
+            let start = line.clone();
+            line.literal("=")?;
+            let high = line.number()?;
+            if high < low {
+                return Err(start.error(format!("a range ending at {low} or above")));
+            }
+            Comparator::Between(low, high)
+        } else {
+            Comparator::Equal(low)
+        }
     };
 
-    let mut latest = 0;
-    for aunt in input {
-        if aunt.part1_eq(&my_aunt) {
-            latest = aunt.name;
+    Ok(Constraint {
+        compound,
+        comparator,
+    })
+}
+
+impl FromStr for Tape {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut constraints = vec![];
+        for mut line in lines(s).filter(|line| !line.is_empty()) {
+            loop {
+                constraints.push(constraint(&mut line)?);
+                if !line.eat(",") {
+                    break;
+                }
+                line.skip_whitespace();
+            }
+            line.end()?;
         }
+        Ok(Tape(constraints))
     }
-    latest
 }
 
-#[aoc(day16, part2)]
-fn part2(input: &Vec<Aunt>) -> u16 {
-    let my_aunt = Aunt {
-        name: 0,
-        children: 3,
-        cats: 7,
-        samoyeds: 2,
-        pomeranians: 3,
-        akitas: 0,
-        vizslas: 0,
-        goldfish: 5,
-        trees: 3,
-        cars: 2,
-        perfumes: 1,
-    };
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Passed(u32),
+    Failed(u32),
+    /// The aunt's record does not mention the compound, so it could be anything
+    Unknown,
+}
 
-    let mut aunts = vec![];
-    for aunt in input {
-        if aunt.part2_eq(&my_aunt) {
-            aunts.push(aunt.name);
+/// How an aunt fares against each constraint of a tape, and what else is known about her
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation<'a> {
+    pub aunt: &'a Aunt,
+    pub checks: Vec<(&'a Constraint, Outcome)>,
+    /// Compounds of the aunt's record the tape says nothing about, sorted
+    pub unchecked: Vec<&'a str>,
+}
+
+impl Explanation<'_> {
+    pub fn matches(&self) -> bool {
+        !self
+            .checks
+            .iter()
+            .any(|(_, outcome)| matches!(outcome, Outcome::Failed(_)))
+    }
+}
+
+impl Display for Explanation<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let verdict = if self.matches() {
+            "matches"
+        } else {
+            "ruled out"
+        };
+        write!(f, "Sue {} {verdict}:", self.aunt.number)?;
+
+        let mut separator = " ";
+        for (constraint, outcome) in &self.checks {
+            let condition = format!("{} {}", constraint.compound, constraint.comparator);
+            match outcome {
+                Outcome::Passed(value) => write!(f, "{separator}{condition} passed ({value})")?,
+                Outcome::Failed(value) => write!(f, "{separator}{condition} failed ({value})")?,
+                Outcome::Unknown => write!(f, "{separator}{condition} unknown")?,
+            }
+            separator = ", ";
+        }
+        if !self.unchecked.is_empty() {
+            write!(f, "; not on the tape: {}", self.unchecked.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Tape {
+    pub fn explain<'a>(&'a self, aunt: &'a Aunt) -> Explanation<'a> {
+        let checks = self
+            .0
+            .iter()
+            .map(|constraint| {
+                let outcome = match aunt.compounds.get(&constraint.compound) {
+                    Some(&value) if constraint.comparator.accepts(value) => Outcome::Passed(value),
+                    Some(&value) => Outcome::Failed(value),
+                    None => Outcome::Unknown,
+                };
+                (constraint, outcome)
+            })
+            .collect();
+
+        let mut unchecked: Vec<&str> = aunt
+            .compounds
+            .keys()
+            .map(String::as_str)
+            .filter(|compound| !self.0.iter().any(|c| c.compound == *compound))
+            .collect();
+        unchecked.sort_unstable();
+
+        Explanation {
+            aunt,
+            checks,
+            unchecked,
         }
     }
-    println!("{aunts:?}");
-    *aunts.last().unwrap()
+
+    /// Every aunt the tape could be about, in the order given
+    pub fn matching<'a>(&'a self, aunts: &'a [Aunt]) -> Vec<Explanation<'a>> {
+        aunts
+            .iter()
+            .map(|aunt| self.explain(aunt))
+            .filter(Explanation::matches)
+            .collect()
+    }
+}
+
+/// The outdated retroencabulator: cats and trees readings are lower bounds, pomeranians and
+/// goldfish ones upper bounds
+fn outdated(mut tape: Tape) -> Tape {
+    for constraint in &mut tape.0 {
+        constraint.comparator = match (constraint.compound.as_str(), constraint.comparator) {
+            ("cats" | "trees", Comparator::Equal(n)) => Comparator::Greater(n),
+            ("pomeranians" | "goldfish", Comparator::Equal(n)) => Comparator::Less(n),
+            (_, comparator) => comparator,
+        }
+    }
+    tape
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SueError {
+    NoMatch,
+    /// Several aunts fit, by number
+    Ambiguous(Vec<u16>),
+}
+
+impl Display for SueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SueError::NoMatch => write!(f, "no aunt matches the tape"),
+            SueError::Ambiguous(numbers) => {
+                let numbers: Vec<String> = numbers.iter().map(u16::to_string).collect();
+                write!(f, "several aunts match the tape: {}", numbers.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for SueError {}
+
+/// The one aunt matching the tape
+fn only(tape: &Tape, aunts: &[Aunt]) -> Result<u16, SueError> {
+    match tape.matching(aunts)[..] {
+        [] => Err(SueError::NoMatch),
+        [ref only] => Ok(only.aunt.number),
+        ref several => Err(SueError::Ambiguous(
+            several.iter().map(|e| e.aunt.number).collect(),
+        )),
+    }
+}
+
+#[aoc_generator(day16)]
+pub fn parse(input: &str) -> Result<Vec<Aunt>, ParseError> {
+    let mut aunts = vec![];
+    for line in lines(input) {
+        aunts.push(Aunt::try_from(line)?)
+    }
+    Ok(aunts)
+}
+
+#[aoc(day16, part1)]
+fn part1(input: &[Aunt]) -> Result<u16, SueError> {
+    only(&READING.parse().unwrap(), input)
+}
+
+#[aoc(day16, part2)]
+fn part2(input: &[Aunt]) -> Result<u16, SueError> {
+    only(&outdated(READING.parse().unwrap()), input)
 }
 
 #[cfg(test)]
@@ -156,22 +307,95 @@ mod tests {
     #[test]
     fn part1_example() {
         assert_eq!(
-            part1(&parse(
-                r#"Sue 1: children: 1, cars: 8, vizslas: 7
+            part1(
+                &parse(
+                    r#"Sue 1: children: 1, cars: 8, vizslas: 7
                    Sue 2: akitas: 0, perfumes: 1, children: 3"#
-            )),
-            2
+                )
+                .unwrap()
+            ),
+            Ok(2)
         );
     }
 
     #[test]
     fn part2_example() {
         assert_eq!(
-            part2(&parse(
-                r#"Sue 1: children: 1, cars: 8, vizslas: 7
+            part2(
+                &parse(
+                    r#"Sue 1: children: 1, cars: 8, vizslas: 7
                    Sue 2: perfumes: 1, cats: 10, pomeranians: 1"#
-            )),
-            2
+                )
+                .unwrap()
+            ),
+            Ok(2)
+        );
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("Sue 1: children: 1\nSue 2: dogs 3").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 12));
+        assert_eq!(error.expected, "':'");
+
+        let error = parse("Sue 1: cats: 1, cats: 2").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 17));
+        assert_eq!(error.expected, "a compound not given yet");
+
+        let error = "cats: 5..=3".parse::<Tape>().unwrap_err();
+        assert_eq!((error.line, error.column), (1, 10));
+        let error = "cats: >".parse::<Tape>().unwrap_err();
+        assert_eq!((error.column, error.expected.as_str()), (8, "number"));
+    }
+
+    #[test]
+    fn test_tape() {
+        let tape: Tape = "cats: 7, trees: >3\n\n  goldfish: < 5\ndogs: =2, cars: 1..=3"
+            .parse()
+            .unwrap();
+        let comparators: Vec<Comparator> = tape.0.iter().map(|c| c.comparator).collect();
+        assert_eq!(
+            comparators,
+            [
+                Comparator::Equal(7),
+                Comparator::Greater(3),
+                Comparator::Less(5),
+                Comparator::Equal(2),
+                Comparator::Between(1, 3)
+            ]
+        );
+        assert_eq!(
+            outdated(READING.parse().unwrap()).0[7].comparator,
+            Comparator::Greater(3)
+        );
+    }
+
+    #[test]
+    fn test_explanations() {
+        let aunts = parse(
+            "Sue 1: cats: 8, dogs: 2, unicorns: 1
+             Sue 2: cats: 8, dogs: 3
+             Sue 3: trees: 4, cars: 2",
+        )
+        .unwrap();
+        let tape: Tape = "cats: >7, dogs: 2, cars: 1..=3".parse().unwrap();
+
+        let matching = tape.matching(&aunts);
+        let numbers: Vec<u16> = matching.iter().map(|e| e.aunt.number).collect();
+        assert_eq!(numbers, [1, 3]);
+        assert_eq!(
+            matching[0].to_string(),
+            "Sue 1 matches: cats > 7 passed (8), dogs = 2 passed (2), cars in 1..=3 unknown; \
+             not on the tape: unicorns"
+        );
+        assert_eq!(
+            tape.explain(&aunts[1]).to_string(),
+            "Sue 2 ruled out: cats > 7 passed (8), dogs = 2 failed (3), cars in 1..=3 unknown"
+        );
+        assert_eq!(only(&tape, &aunts), Err(SueError::Ambiguous(vec![1, 3])));
+        assert_eq!(
+            only(&"cats: 0, trees: 0".parse().unwrap(), &aunts),
+            Err(SueError::NoMatch)
         );
     }
 }
@@ -1,70 +1,161 @@
+use advent_of_code_common::{
+    big::BigUint,
+    parse::{lines, ParseError},
+};
 use aoc_runner_derive::{aoc, aoc_generator};
+
 #[aoc_generator(day17)]
-fn parse(input: &str) -> Vec<u8> {
+fn parse(input: &str) -> Result<Vec<u32>, ParseError> {
     let mut containers = vec![];
-    for line in input.lines() {
-        containers.push(line.trim().parse().unwrap())
+    for mut line in lines(input) {
+        containers.push(line.number()?);
+        line.end()?;
     }
-    containers
+    Ok(containers)
 }
 
-fn fill(input: &[u8], amount: u8) -> u8 {
-    if input.is_empty() {
-        return 0;
+/// How many ways there are to fill `target` exactly, by the number of containers used: the
+/// count at index `k` is for `k` containers. Containers of the same size are still different
+/// containers.
+pub fn count(containers: &[u32], target: u32) -> Vec<BigUint> {
+    let target = target as usize;
+    // ways[k][volume]: the subsets of the containers so far with `k` of them holding `volume`
+    let mut ways = vec![vec![BigUint::zero(); target + 1]; containers.len() + 1];
+    ways[0][0] = BigUint::from(1u64);
+
+    for (seen, &size) in containers.iter().enumerate() {
+        let size = size as usize;
+        if size > target {
+            continue;
+        }
+        for k in (0..=seen).rev() {
+            let (fewer, more) = ways.split_at_mut(k + 1);
+            for volume in (0..=target - size).rev() {
+                if !fewer[k][volume].is_zero() {
+                    more[0][volume + size] += &fewer[k][volume];
+                }
+            }
+        }
     }
 
-    let mut combinations = 0;
-    let capacity = input[0];
-
-    if capacity == amount {
-        combinations += 1
-    }
+    ways.into_iter()
+        .map(|mut by_volume| by_volume.swap_remove(target))
+        .collect()
+}
 
-    combinations += fill(&input[1..], amount);
-    if amount > capacity {
-        combinations += fill(&input[1..], amount - capacity);
-    }
+/// One choice still to explore: whether to take container `next` onwards, with `remaining`
+/// left to fill, after the first `len` containers chosen so far and `pick` if given
+#[derive(Debug, Clone, Copy)]
+struct Task {
+    next: usize,
+    remaining: u32,
+    len: usize,
+    pick: Option<usize>,
+}
 
-    combinations
+/// Every set of containers filling a target exactly, as the sorted indices of the containers,
+/// those with the first containers coming first. Sets that cannot add up are never started.
+#[derive(Debug, Clone)]
+pub struct Subsets<'a> {
+    containers: &'a [u32],
+    /// reachable[i][volume]: whether the containers from `i` on can hold exactly `volume`
+    reachable: Vec<Vec<bool>>,
+    tasks: Vec<Task>,
+    chosen: Vec<usize>,
 }
 
-fn fill_count(input: &[u8], amount: u8) -> Vec<u8> {
-    if input.is_empty() {
-        return vec![];
+pub fn subsets(containers: &[u32], target: u32) -> Subsets<'_> {
+    let n = containers.len();
+    let mut reachable = vec![vec![false; target as usize + 1]; n + 1];
+    reachable[n][0] = true;
+    for i in (0..n).rev() {
+        let size = containers[i] as usize;
+        for volume in 0..=target as usize {
+            reachable[i][volume] =
+                reachable[i + 1][volume] || (size <= volume && reachable[i + 1][volume - size]);
+        }
     }
 
-    let mut quantity = vec![];
-    let capacity = input[0];
-
-    if capacity == amount {
-        quantity.push(1);
+    let mut tasks = vec![];
+    if reachable[0][target as usize] {
+        tasks.push(Task {
+            next: 0,
+            remaining: target,
+            len: 0,
+            pick: None,
+        });
     }
 
-    quantity.extend(fill_count(&input[1..], amount));
-
-    if amount > capacity {
-        quantity.extend(
-            fill_count(&input[1..], amount - capacity)
-                .into_iter()
-                .map(|c| c + 1)
-                .collect::<Vec<u8>>()
-                .iter(),
-        );
+    Subsets {
+        containers,
+        reachable,
+        tasks,
+        chosen: vec![],
     }
+}
 
-    quantity
+impl Iterator for Subsets<'_> {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        while let Some(task) = self.tasks.pop() {
+            self.chosen.truncate(task.len);
+            self.chosen.extend(task.pick);
+            if task.remaining == 0 {
+                // Done, give or take the empty containers left
+                let len = self.chosen.len();
+                for empty in (task.next..self.containers.len()).rev() {
+                    if self.containers[empty] == 0 {
+                        self.tasks.push(Task {
+                            next: empty + 1,
+                            remaining: 0,
+                            len,
+                            pick: Some(empty),
+                        });
+                    }
+                }
+                return Some(self.chosen.clone());
+            }
+
+            // Every task can still be completed, so the containers are not all used up yet
+            let i = task.next;
+            let size = self.containers[i];
+            let can_reach = |volume: u32| self.reachable[i + 1][volume as usize];
+            let len = self.chosen.len();
+            let mut push = |remaining, pick| {
+                self.tasks.push(Task {
+                    next: i + 1,
+                    remaining,
+                    len,
+                    pick,
+                })
+            };
+
+            // Pushed last so that taking the container is explored first
+            let leave = can_reach(task.remaining);
+            let take = size <= task.remaining && can_reach(task.remaining - size);
+            if leave {
+                push(task.remaining, None);
+            }
+            if take {
+                push(task.remaining - size, Some(i));
+            }
+        }
+        None
+    }
 }
 
 #[aoc(day17, part1)]
-fn part1(input: &[u8]) -> u8 {
-    fill(input, 150)
+fn part1(input: &[u32]) -> BigUint {
+    count(input, 150).iter().sum()
 }
 
 #[aoc(day17, part2)]
-fn part2(input: &[u8]) -> u8 {
-    let v = fill_count(input, 150);
-    let min = v.iter().min().unwrap();
-    v.iter().filter(|&v| v == min).count() as u8
+fn part2(input: &[u32]) -> BigUint {
+    count(input, 150)
+        .into_iter()
+        .find(|ways| !ways.is_zero())
+        .unwrap_or_default()
 }
 
 #[cfg(test)]
@@ -74,33 +165,81 @@ mod tests {
     #[test]
     fn part1_example() {
         assert_eq!(
-            fill(
+            count(
                 &parse(
                     r#" 20
                     15
                     10
                     5
                     5"#
-                ),
+                )
+                .unwrap(),
                 25
-            ),
-            4
+            )
+            .iter()
+            .sum::<BigUint>(),
+            BigUint::from(4u64)
         );
     }
 
     #[test]
     fn part2_example() {
-        let v = fill_count(
-            &parse(
-                r#" 20
+        let containers = parse(
+            r#" 20
                     15
                     10
                     5
                     5"#,
-            ),
-            25,
-        );
-        let min = v.iter().min().unwrap();
-        assert_eq!(v.iter().filter(|&v| v == min).count(), 3);
+        )
+        .unwrap();
+        let by_count: Vec<String> = count(&containers, 25)
+            .iter()
+            .map(BigUint::to_string)
+            .collect();
+        assert_eq!(by_count, ["0", "0", "3", "1", "0", "0"]);
+
+        let all: Vec<Vec<usize>> = subsets(&containers, 25).collect();
+        assert_eq!(all, [vec![0, 3], vec![0, 4], vec![1, 2], vec![1, 3, 4]]);
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("20\n15\n4294967296").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 1));
+        assert_eq!(error.expected, "number fitting u32");
+    }
+
+    #[test]
+    fn test_against_brute_force() {
+        let containers = [7, 3, 12, 5, 5, 0, 9, 3, 14, 1, 8, 6];
+        for target in [0, 1, 17, 30, 50, 73, 74] {
+            let mut by_count = vec![0u64; containers.len() + 1];
+            let mut expected = vec![];
+            for mask in 0..1u32 << containers.len() {
+                let chosen: Vec<usize> = (0..containers.len())
+                    .filter(|i| mask & 1 << i != 0)
+                    .collect();
+                if chosen.iter().map(|&i| containers[i]).sum::<u32>() == target {
+                    by_count[chosen.len()] += 1;
+                    expected.push(chosen);
+                }
+            }
+
+            let by_count: Vec<BigUint> = by_count.into_iter().map(BigUint::from).collect();
+            assert_eq!(count(&containers, target), by_count, "{target}");
+            let mut found: Vec<Vec<usize>> = subsets(&containers, target).collect();
+            found.sort();
+            expected.sort();
+            assert_eq!(found, expected, "{target}");
+        }
+    }
+
+    #[test]
+    fn test_wide_counts() {
+        // 100 choose 50 ways, beyond any u64
+        let ways = count(&[1; 100], 50);
+        assert_eq!(ways[50].to_string(), "100891344545564193334812497256");
+        assert!(ways.iter().enumerate().all(|(k, w)| k == 50 || w.is_zero()));
+        assert_eq!(subsets(&[1; 100], 50).take(3).count(), 3);
     }
 }
@@ -1,90 +1,114 @@
-use aoc_runner_derive::aoc;
-
-#[derive(Default, Debug, Clone)]
-struct Grid {
-    height: usize,
-    width: usize,
-    fixed_lights: Vec<(usize, usize)>,
-    lights: Vec<Vec<bool>>,
+use std::fmt::Display;
+
+use advent_of_code_common::parse::{check_map, ParseError};
+use aoc_runner_derive::{aoc, aoc_generator};
+use grid::automaton::{Automaton, Rule, RuleError, Topology};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LightsError {
+    Parse(ParseError),
+    Rule(RuleError),
+    /// The lights are a fixed rectangle, not a pattern on an unbounded plane
+    Unbounded,
 }
 
-impl Grid {
-    fn step(&mut self) {
-        let snapshot = self.lights.clone();
-
-        let around: [(i16, i16); 8] = [
-            (-1, 0),
-            (-1, -1),
-            (0, -1),
-            (1, -1),
-            (1, 0),
-            (1, 1),
-            (0, 1),
-            (-1, 1),
-        ];
-        for (y, row) in snapshot.iter().enumerate() {
-            for (x, &current_on) in row.iter().enumerate() {
-                let mut neighbors_on = 0;
-                for (dx, dy) in around {
-                    let (x, y) = (x as i16, y as i16);
-
-                    if x + dx >= 0
-                        && x + dx < self.width as i16
-                        && y + dy >= 0
-                        && y + dy < self.height as i16
-                        && snapshot[(y + dy) as usize][(x + dx) as usize]
-                    {
-                        neighbors_on += 1
-                    }
-                }
-
-                if current_on {
-                    if !(neighbors_on == 2 || neighbors_on == 3) {
-                        self.lights[y][x] = false;
-                    }
-                } else if neighbors_on == 3 {
-                    self.lights[y][x] = true;
-                }
-            }
+impl Display for LightsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LightsError::Parse(e) => e.fmt(f),
+            LightsError::Rule(e) => e.fmt(f),
+            LightsError::Unbounded => write!(f, "the lights need a bounded or toroidal board"),
         }
+    }
+}
 
-        for (fx, fy) in &self.fixed_lights {
-            self.lights[*fy][*fx] = true
+impl std::error::Error for LightsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LightsError::Parse(e) => Some(e),
+            _ => None,
         }
     }
+}
 
-    fn always_on(&mut self, fixed: Vec<(usize, usize)>) {
-        self.fixed_lights = fixed;
-        for (fx, fy) in &self.fixed_lights {
-            self.lights[*fy][*fx] = true
-        }
+impl From<ParseError> for LightsError {
+    fn from(e: ParseError) -> Self {
+        LightsError::Parse(e)
     }
+}
 
-    fn count_on(&self) -> u16 {
-        self.lights
-            .iter()
-            .map(|v| v.iter().filter(|&v| *v).count() as u16)
-            .sum()
+impl From<RuleError> for LightsError {
+    fn from(e: RuleError) -> Self {
+        LightsError::Rule(e)
     }
 }
 
-impl From<&str> for Grid {
-    fn from(value: &str) -> Self {
-        let mut g = Grid::default();
+/// The lights follow Conway's rule on a board whose edges are dark
+#[derive(Debug, Clone)]
+pub struct Grid {
+    lights: Automaton,
+    width: usize,
+    height: usize,
+}
 
-        for line in value.lines() {
-            g.width = line.len();
-            g.height += 1;
-            g.lights.push(line.chars().map(|c| c == '#').collect())
+impl Grid {
+    /// The lights of `input` under another rule, or on a board whose opposite edges meet
+    pub fn with_rule(input: &str, rule: Rule, topology: Topology) -> Result<Self, LightsError> {
+        if topology == Topology::Infinite {
+            return Err(LightsError::Unbounded);
         }
+        check_map(input, |c| c == '#' || c == '.', "one of '#', '.'")?;
+        let lights = grid::Grid::parse(input, |c| c == '#');
+        Ok(Grid {
+            lights: Automaton::new(&lights, rule, topology)?,
+            width: lights.width(),
+            height: lights.height(),
+        })
+    }
 
-        g
+    pub fn step(&mut self) {
+        self.lights.step()
+    }
+
+    /// Part 2's broken grid, whose four corners are stuck on
+    pub fn stick_corners(&mut self) {
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+        let (width, height) = (self.width as i64, self.height as i64);
+
+        for corner in [
+            (0, 0),
+            (width - 1, 0),
+            (width - 1, height - 1),
+            (0, height - 1),
+        ] {
+            self.lights.pin(corner, true);
+        }
+    }
+
+    pub fn count_on(&self) -> usize {
+        self.lights.population()
+    }
+
+    /// Lit lights white, the others black
+    pub fn picture(&self) -> grid::Grid<u8> {
+        let mut picture = grid::Grid::new(self.width, self.height, 0);
+        for (x, y) in self.lights.live_cells() {
+            picture[(x as usize, y as usize)] = 255;
+        }
+        picture
     }
 }
 
+#[aoc_generator(day18)]
+fn parse(input: &str) -> Result<Grid, LightsError> {
+    Grid::with_rule(input, Rule::LIFE, Topology::Bounded)
+}
+
 #[aoc(day18, part1)]
-fn part1(input: &str) -> u16 {
-    let mut g: Grid = input.into();
+fn part1(grid: &Grid) -> usize {
+    let mut g = grid.clone();
     for _ in 0..100 {
         g.step()
     }
@@ -92,15 +116,9 @@ fn part1(input: &str) -> u16 {
 }
 
 #[aoc(day18, part2)]
-fn part2(input: &str) -> u16 {
-    let mut g: Grid = input.into();
-
-    g.always_on(vec![
-        (0, 0),
-        (g.width - 1, 0),
-        (g.width - 1, g.height - 1),
-        (0, g.height - 1),
-    ]);
+fn part2(grid: &Grid) -> usize {
+    let mut g = grid.clone();
+    g.stick_corners();
 
     for _ in 0..100 {
         g.step()
@@ -121,11 +139,47 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(INPUT), 4);
+        assert_eq!(part1(&parse(INPUT).unwrap()), 4);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(INPUT), 7);
+        assert_eq!(part2(&parse(INPUT).unwrap()), 7);
+    }
+
+    #[test]
+    fn test_picture() {
+        let picture = parse(INPUT).unwrap().picture();
+        assert_eq!(picture.row(0), [0, 255, 0, 255, 0, 255]);
+        assert_eq!(picture.count(|&level| level == 255), 15);
+    }
+
+    #[test]
+    fn test_boards() {
+        let mut empty = parse("").unwrap();
+        empty.stick_corners();
+        assert_eq!(empty.count_on(), 0);
+        assert_eq!(empty.picture().width(), 0);
+
+        let mut torus = Grid::with_rule(INPUT, Rule::LIFE, Topology::Toroidal).unwrap();
+        torus.stick_corners();
+        assert_eq!(torus.count_on(), 17);
+
+        let error = Grid::with_rule(INPUT, Rule::LIFE, Topology::Infinite).unwrap_err();
+        assert_eq!(error, LightsError::Unbounded);
+    }
+
+    #[test]
+    fn parse_error() {
+        let Err(LightsError::Parse(error)) = parse(".#.\n#..#") else {
+            panic!("a ragged board should not parse")
+        };
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(error.expected, "end of row");
+
+        let Err(LightsError::Parse(error)) = parse(".#.\n#o.") else {
+            panic!("an unknown light should not parse")
+        };
+        assert_eq!((error.line, error.column), (2, 2));
     }
 }
@@ -1,102 +1,413 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
 
+use advent_of_code_common::parse::{lines, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 
-type Data = (
-    HashMap<String, Vec<String>>,
-    String,
-    HashMap<String, String>,
-);
+#[derive(Debug)]
+pub struct Machine {
+    replacements: Vec<(String, String)>,
+    molecule: String,
+}
 
 #[aoc_generator(day19)]
-fn parse(input: &str) -> Data {
-    let mut replacements = HashMap::new();
-    let mut inverse_replacements = HashMap::new();
-    let re = regex::Regex::new(r"(?P<key>\w+) => (?P<value>\w+)").unwrap();
+pub fn parse(input: &str) -> Result<Machine, ParseError> {
+    let mut replacements = vec![];
 
     let mut last_line = false;
     let mut molecule = String::new();
-    for line in input.lines() {
+    for mut line in lines(input) {
         if line.is_empty() {
             last_line = true;
             continue;
         }
         if last_line {
-            molecule += line;
+            molecule += line.word()?;
+            line.end()?;
             continue;
         }
-        let data = re.captures(line).unwrap();
+        let key = line.word()?.to_string();
+        line.literal(" => ")?;
+        let value = line.word()?.to_string();
+        line.end()?;
 
-        replacements
-            .entry(data["key"].to_string())
-            .and_modify(|v: &mut Vec<String>| v.push(data["value"].to_string()))
-            .or_insert(vec![data["value"].to_string()]);
-        inverse_replacements.insert(data["value"].to_string(), data["key"].to_string());
+        replacements.push((key, value));
     }
 
-    (replacements, molecule, inverse_replacements)
+    Ok(Machine {
+        replacements,
+        molecule,
+    })
 }
 
 #[aoc(day19, part1)]
-fn part1(input: &Data) -> usize {
-    let replacements = input.0.clone();
-    let molecule = input.1.clone();
+fn part1(input: &Machine) -> usize {
+    let molecule = &input.molecule;
 
     let mut new_molecules: HashSet<String> = HashSet::new();
-    for key in replacements.keys().collect::<Vec<&String>>() {
-        for replacement in input.0[key].clone() {
-            let mut remaining = molecule.clone();
-            let mut previous = String::new();
-            while let Some(next) = remaining.find(key) {
-                let (first, last) = remaining.split_at(next);
-                previous += first;
-                let new_molecule = previous.to_string() + &replacement + &last[key.len()..];
-                new_molecules.insert(new_molecule);
-                previous += key;
-                remaining = last[key.len()..].to_string();
-            }
+    for (key, replacement) in &input.replacements {
+        for (i, _) in molecule.match_indices(key.as_str()) {
+            new_molecules.insert(format!(
+                "{}{replacement}{}",
+                &molecule[..i],
+                &molecule[i + key.len()..]
+            ));
         }
     }
     new_molecules.len()
 }
 
-#[aoc(day19, part2)]
-fn part2(input: &Data) -> usize {
-    let molecule = input.1.clone();
-    let ireplacements = input.2.clone();
-
-    let mut keys: Vec<String> = ireplacements.keys().cloned().collect();
-    let mut rng = rand::rng();
-
-    loop {
-        // Get a random sequence of replacements and try to reduce the current molecule down to 'e'
-        // Non deterministic for generic case, but works and very fast for the order of the problem input
-        rand::seq::SliceRandom::shuffle(keys.as_mut_slice(), &mut rng);
-
-        let mut temp = molecule.clone();
-        let mut steps = 0;
-        let mut stuck = false;
-
-        while temp != "e" {
-            let mut found = false;
-            for key in &keys {
-                if temp.contains(key.as_str()) {
-                    temp = temp.replacen(key.as_str(), &ireplacements[key], 1);
-                    steps += 1;
-                    found = true;
-                    break;
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReductionError {
+    /// No sequence of replacements turns `e` into the molecule
+    Unreachable,
+    /// A replacement starting from several elements, which the grammar cannot express
+    NotContextFree(String),
+}
+
+impl Display for ReductionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReductionError::Unreachable => write!(f, "unreachable: e never becomes the molecule"),
+            ReductionError::NotContextFree(key) => {
+                write!(
+                    f,
+                    "'{key}' is not a single element, replacements must start from one"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReductionError {}
+
+/// Splits a molecule into its elements: an uppercase letter and the lowercase ones after it,
+/// or a leading run of lowercase letters such as `e`
+fn elements(molecule: &str) -> Vec<&str> {
+    let mut starts: Vec<usize> = molecule
+        .char_indices()
+        .filter(|&(i, c)| i == 0 || !c.is_ascii_lowercase())
+        .map(|(i, _)| i)
+        .collect();
+    starts.push(molecule.len());
+    starts.windows(2).map(|w| &molecule[w[0]..w[1]]).collect()
+}
+
+/// How the chart derives an element over a span, to rebuild the cheapest derivation
+#[derive(Debug, Clone, Copy)]
+enum Back {
+    /// The span is that one element of the molecule
+    Token,
+    /// A single element replacement over the same span
+    Unit(usize),
+    /// A longer replacement, whose last element starts at `at`
+    Split { rule: usize, at: usize },
+}
+
+/// Every span of a molecule with the elements deriving it at their lowest cost, and the rule
+/// prefixes `(rule, m)` whose `m` first elements do, with where the `m`-th one starts
+struct Chart {
+    n: usize,
+    best: Vec<HashMap<usize, (usize, Back)>>,
+    partial: Vec<HashMap<(usize, usize), (usize, usize)>>,
+}
+
+impl Chart {
+    fn span(&self, i: usize, j: usize) -> usize {
+        i * (self.n + 1) + j
+    }
+}
+
+/// The replacements as a context-free grammar over interned elements
+struct Grammar<'a> {
+    names: Vec<&'a str>,
+    ids: HashMap<&'a str, usize>,
+    rules: Vec<(usize, Vec<usize>)>,
+}
+
+impl<'a> Grammar<'a> {
+    fn new(replacements: &'a [(String, String)]) -> Result<Self, ReductionError> {
+        let mut grammar = Grammar {
+            names: vec![],
+            ids: HashMap::new(),
+            rules: vec![],
+        };
+
+        for (key, value) in replacements {
+            let from = match elements(key)[..] {
+                [element] => grammar.id(element),
+                _ => return Err(ReductionError::NotContextFree(key.clone())),
+            };
+            let to = elements(value).into_iter().map(|e| grammar.id(e)).collect();
+            grammar.rules.push((from, to));
+        }
+
+        Ok(grammar)
+    }
+
+    fn id(&mut self, element: &'a str) -> usize {
+        *self.ids.entry(element).or_insert_with(|| {
+            self.names.push(element);
+            self.names.len() - 1
+        })
+    }
+
+    /// Fills the chart bottom up: which elements can grow into each span of the molecule and at
+    /// what cost, plus which rules have their first few elements covering it. Cubic in the
+    /// molecule's length whatever the replacements, and exact where a greedy reduction can get
+    /// stuck.
+    fn chart(&self, molecule: &str) -> Chart {
+        let tokens: Vec<Option<usize>> = elements(molecule)
+            .into_iter()
+            .map(|e| self.ids.get(e).copied())
+            .collect();
+        let n = tokens.len();
+        let mut chart = Chart {
+            n,
+            best: vec![HashMap::new(); (n + 1) * (n + 1)],
+            partial: vec![HashMap::new(); (n + 1) * (n + 1)],
+        };
+
+        for length in 1..=n {
+            for i in 0..=n - length {
+                let j = i + length;
+                let mut here: HashMap<usize, (usize, Back)> = HashMap::new();
+                let mut prefixes: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+
+                if let (1, Some(&Some(token))) = (length, tokens.get(i)) {
+                    here.insert(token, (0, Back::Token));
                 }
+
+                for k in i + 1..j {
+                    for (&(rule, m), &(left, _)) in &chart.partial[chart.span(i, k)] {
+                        let (from, to) = &self.rules[rule];
+                        let Some(&(right, _)) = chart.best[chart.span(k, j)].get(&to[m]) else {
+                            continue;
+                        };
+                        let cost = left + right;
+                        if m + 1 == to.len() {
+                            if here.get(from).is_none_or(|&(old, _)| cost + 1 < old) {
+                                here.insert(*from, (cost + 1, Back::Split { rule, at: k }));
+                            }
+                        } else if prefixes
+                            .get(&(rule, m + 1))
+                            .is_none_or(|&(old, _)| cost < old)
+                        {
+                            prefixes.insert((rule, m + 1), (cost, k));
+                        }
+                    }
+                }
+
+                // Single element replacements, until no cheaper way in is found
+                let mut changed = true;
+                while changed {
+                    changed = false;
+                    for (rule, (from, to)) in self.rules.iter().enumerate() {
+                        let [to] = to[..] else { continue };
+                        let Some(cost) = here.get(&to).map(|&(c, _)| c + 1) else {
+                            continue;
+                        };
+                        if here.get(from).is_none_or(|&(old, _)| cost < old) {
+                            here.insert(*from, (cost, Back::Unit(rule)));
+                            changed = true;
+                        }
+                    }
+                }
+
+                for (rule, (_, to)) in self.rules.iter().enumerate() {
+                    if let (true, Some(&(cost, _))) = (to.len() > 1, here.get(&to[0])) {
+                        prefixes.insert((rule, 1), (cost, i));
+                    }
+                }
+
+                let span = chart.span(i, j);
+                chart.best[span] = here;
+                chart.partial[span] = prefixes;
+            }
+        }
+
+        chart
+    }
+
+    /// The spans and elements the `m` first elements of `rule` cover from `i` to `j`
+    fn prefix(
+        &self,
+        chart: &Chart,
+        rule: usize,
+        m: usize,
+        (i, j): (usize, usize),
+    ) -> Vec<(usize, usize, usize)> {
+        let to = &self.rules[rule].1;
+        if m == 1 {
+            return vec![(i, j, to[0])];
+        }
+
+        let (_, at) = chart.partial[chart.span(i, j)][&(rule, m)];
+        let mut children = self.prefix(chart, rule, m - 1, (i, at));
+        children.push((at, j, to[m - 1]));
+        children
+    }
+
+    /// Replays the cheapest way `element` grows into the span `i..j`, which starts at byte
+    /// `offset` of `current`. Everything left of it is already expanded, so this is the
+    /// leftmost derivation.
+    fn expand(
+        &self,
+        chart: &Chart,
+        (i, j, element): (usize, usize, usize),
+        current: &mut String,
+        offset: &mut usize,
+        steps: &mut Vec<Step>,
+    ) {
+        let children = match chart.best[chart.span(i, j)][&element].1 {
+            Back::Token => {
+                *offset += self.names[element].len();
+                return;
+            }
+            Back::Unit(rule) => (rule, vec![(i, j, self.rules[rule].1[0])]),
+            Back::Split { rule, at } => {
+                let to = &self.rules[rule].1;
+                let mut children = self.prefix(chart, rule, to.len() - 1, (i, at));
+                children.push((at, j, to[to.len() - 1]));
+                (rule, children)
+            }
+        };
+
+        let (rule, children) = children;
+        let (from, to) = &self.rules[rule];
+        let from = self.names[*from];
+        let to: String = to.iter().map(|&e| self.names[e]).collect();
+        current.replace_range(*offset..*offset + from.len(), &to);
+        steps.push(Step {
+            rule,
+            from: from.to_string(),
+            to,
+            at: *offset,
+            molecule: current.clone(),
+        });
+
+        for child in children {
+            self.expand(chart, child, current, offset, steps);
+        }
+    }
+
+    /// The shortest derivation of `molecule` from `start`, if there is one
+    fn derive(&self, start: &str, molecule: &str) -> Option<Vec<Step>> {
+        let chart = self.chart(molecule);
+        let start = *self.ids.get(start)?;
+        chart.best[chart.span(0, chart.n)].get(&start)?;
+
+        let mut current = self.names[start].to_string();
+        let mut steps = vec![];
+        self.expand(
+            &chart,
+            (0, chart.n, start),
+            &mut current,
+            &mut 0,
+            &mut steps,
+        );
+        Some(steps)
+    }
+}
+
+/// One replacement of a derivation: `from => to`, the `rule`-th replacement of the input, at
+/// byte `at` of the previous molecule, giving `molecule`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub rule: usize,
+    pub from: String,
+    pub to: String,
+    pub at: usize,
+    pub molecule: String,
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} => {} at {}: {}",
+            self.from, self.to, self.at, self.molecule
+        )
+    }
+}
+
+/// The fewest replacements turning `e` into the machine's molecule, step by step
+pub fn derive(machine: &Machine) -> Result<Vec<Step>, ReductionError> {
+    Grammar::new(&machine.replacements)?
+        .derive("e", &machine.molecule)
+        .ok_or(ReductionError::Unreachable)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum InvalidDerivation {
+    /// Step `step` uses a replacement the machine does not have
+    UnknownReplacement { step: usize },
+    /// Step `step` replaces something else than its `from` at its position
+    NoMatch { step: usize },
+    /// Step `step` claims another molecule than the replacement makes
+    WrongMolecule { step: usize, expected: String },
+    /// The derivation ends on another molecule than the machine's
+    WrongTarget { molecule: String },
+}
+
+impl Display for InvalidDerivation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvalidDerivation::UnknownReplacement { step } => {
+                write!(f, "step {step}: no such replacement")
+            }
+            InvalidDerivation::NoMatch { step } => {
+                write!(f, "step {step}: the replaced element is not there")
             }
-            if !found {
-                stuck = true;
-                break;
+            InvalidDerivation::WrongMolecule { step, expected } => {
+                write!(f, "step {step}: the replacement gives {expected}")
+            }
+            InvalidDerivation::WrongTarget { molecule } => {
+                write!(f, "the derivation ends on {molecule}")
             }
         }
+    }
+}
+
+impl std::error::Error for InvalidDerivation {}
+
+/// Replays `steps` from `e`, checking each one against the machine's replacements, then that
+/// they end on its molecule. Steps are numbered from 1 in the errors.
+pub fn verify(machine: &Machine, steps: &[Step]) -> Result<(), InvalidDerivation> {
+    let mut current = "e".to_string();
 
-        if !stuck {
-            return steps;
+    for (n, step) in steps.iter().enumerate().map(|(i, step)| (i + 1, step)) {
+        let replacement = (step.from.clone(), step.to.clone());
+        if machine.replacements.get(step.rule) != Some(&replacement) {
+            return Err(InvalidDerivation::UnknownReplacement { step: n });
+        }
+        if current
+            .get(step.at..)
+            .is_none_or(|rest| !rest.starts_with(&step.from))
+        {
+            return Err(InvalidDerivation::NoMatch { step: n });
+        }
+
+        current.replace_range(step.at..step.at + step.from.len(), &step.to);
+        if current != step.molecule {
+            return Err(InvalidDerivation::WrongMolecule {
+                step: n,
+                expected: current,
+            });
         }
     }
+
+    if current != machine.molecule {
+        return Err(InvalidDerivation::WrongTarget { molecule: current });
+    }
+    Ok(())
+}
+
+#[aoc(day19, part2)]
+fn part2(input: &Machine) -> Result<usize, ReductionError> {
+    derive(input).map(|steps| steps.len())
 }
 
 #[cfg(test)]
@@ -106,13 +417,16 @@ mod tests {
     #[test]
     fn part1_example() {
         assert_eq!(
-            part1(&parse(
-                r"H => HO
+            part1(
+                &parse(
+                    r"H => HO
 H => OH
 O => HH
 
 HOH"
-            )),
+                )
+                .unwrap()
+            ),
             4
         );
     }
@@ -120,28 +434,100 @@ HOH"
     #[test]
     fn part2_example() {
         assert_eq!(
-            part2(&parse(
-                r"e => H
+            part2(
+                &parse(
+                    r"e => H
 e => O
 H => HO
 H => OH
 O => HH
 
 HOH"
-            )),
-            3
+                )
+                .unwrap()
+            ),
+            Ok(3)
         );
         assert_eq!(
-            part2(&parse(
-                r"e => H
+            part2(
+                &parse(
+                    r"e => H
 e => O
 H => HO
 H => OH
 O => HH
 
 HOHOHO"
-            )),
-            6
+                )
+                .unwrap()
+            ),
+            Ok(6)
+        );
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let machine = parse("e => H\ne => O\nH => HO\n\nOH").unwrap();
+        assert_eq!(part2(&machine), Err(ReductionError::Unreachable));
+        let machine = parse("e => H\nH => e\n\nO").unwrap();
+        assert_eq!(part2(&machine), Err(ReductionError::Unreachable));
+        let machine = parse("e => H\nHO => OH\n\nOH").unwrap();
+        assert_eq!(
+            part2(&machine),
+            Err(ReductionError::NotContextFree("HO".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_greedy_dead_end() {
+        // Undoing Ca => e first leaves eCa, then ee, which nothing reduces
+        let machine = parse("e => CaB\nB => Ca\nCa => e\n\nCaCa").unwrap();
+        assert_eq!(part2(&machine), Ok(2));
+        assert_eq!(elements("eCaRnThFAr"), ["e", "Ca", "Rn", "Th", "F", "Ar"]);
+    }
+
+    #[test]
+    fn test_derivation() {
+        let machine = parse("e => H\ne => O\nH => HO\nH => OH\nO => HH\n\nHOHOHO").unwrap();
+        let steps = derive(&machine).unwrap();
+        assert_eq!(steps.len(), 6);
+        assert_eq!(steps[0].from, "e");
+        assert_eq!(steps[5].molecule, "HOHOHO");
+        assert_eq!(verify(&machine, &steps), Ok(()));
+
+        let mut wrong = steps.clone();
+        wrong[2].rule = 0;
+        assert_eq!(
+            verify(&machine, &wrong),
+            Err(InvalidDerivation::UnknownReplacement { step: 3 })
         );
+
+        let mut wrong = steps.clone();
+        wrong[1].at = 100;
+        assert_eq!(
+            verify(&machine, &wrong),
+            Err(InvalidDerivation::NoMatch { step: 2 })
+        );
+
+        let mut wrong = steps.clone();
+        wrong[0].molecule = "X".to_string();
+        assert!(matches!(
+            verify(&machine, &wrong),
+            Err(InvalidDerivation::WrongMolecule { step: 1, .. })
+        ));
+
+        assert_eq!(
+            verify(&machine, &steps[..5]),
+            Err(InvalidDerivation::WrongTarget {
+                molecule: steps[4].molecule.clone()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("H => HO\nO -> HH\n\nHOH").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(error.expected, "'=>'");
     }
 }
@@ -1,13 +1,19 @@
+use advent_of_code_common::parse::{lines, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 #[aoc_generator(day2)]
-fn parse(input: &str) -> Vec<(u32, u32, u32)> {
+fn parse(input: &str) -> Result<Vec<(u32, u32, u32)>, ParseError> {
     let mut ans = vec![];
-    for line in input.lines() {
-        let parts: Vec<u32> = line.split("x").map(|x| x.parse().unwrap()).collect();
-        ans.push((parts[0], parts[1], parts[2]));
+    for mut line in lines(input) {
+        let length = line.number()?;
+        line.literal("x")?;
+        let width = line.number()?;
+        line.literal("x")?;
+        let height = line.number()?;
+        line.end()?;
+        ans.push((length, width, height));
     }
 
-    ans
+    Ok(ans)
 }
 
 #[aoc(day2, part1)]
@@ -50,14 +56,21 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse("2x3x4")), 58);
-        assert_eq!(part1(&parse("1x1x10")), 43);
-        assert_eq!(part1(&parse("3x11x24")), 771);
+        assert_eq!(part1(&parse("2x3x4").unwrap()), 58);
+        assert_eq!(part1(&parse("1x1x10").unwrap()), 43);
+        assert_eq!(part1(&parse("3x11x24").unwrap()), 771);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse("2x3x4")), 34);
-        assert_eq!(part2(&parse("1x1x10")), 14);
+        assert_eq!(part2(&parse("2x3x4").unwrap()), 34);
+        assert_eq!(part2(&parse("1x1x10").unwrap()), 14);
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("2x3x4\n1x1").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(error.expected, "'x'");
     }
 }
@@ -1,8 +1,19 @@
 use std::collections::HashSet;
 
+use advent_of_code_common::parse::{Cursor, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
-#[aoc_generator(day3, part1)]
-fn parse(input: &str) -> HashSet<(i16, i16)> {
+
+fn check(input: &str) -> Result<(), ParseError> {
+    let mut cursor = Cursor::new(1, input);
+    cursor.take_while(|c| "<>^v".contains(c));
+    if cursor.is_empty() {
+        Ok(())
+    } else {
+        Err(cursor.error("one of '<', '>', '^', 'v'"))
+    }
+}
+
+fn visit(input: &str) -> HashSet<(i16, i16)> {
     let mut houses = HashSet::new();
     let mut position = (0, 0);
     houses.insert(position);
@@ -27,10 +38,18 @@ fn parse(input: &str) -> HashSet<(i16, i16)> {
     houses
 }
 
+#[aoc_generator(day3, part1)]
+fn parse(input: &str) -> Result<HashSet<(i16, i16)>, ParseError> {
+    check(input)?;
+    Ok(visit(input))
+}
+
 type Paths = (HashSet<(i16, i16)>, HashSet<(i16, i16)>);
 
 #[aoc_generator(day3, part2)]
-fn parse2(input: &str) -> Paths {
+fn parse2(input: &str) -> Result<Paths, ParseError> {
+    check(input)?;
+
     let mut santa = String::new();
     let mut rsanta = String::new();
 
@@ -42,7 +61,7 @@ fn parse2(input: &str) -> Paths {
         }
     }
 
-    (parse(&santa), parse(&rsanta))
+    Ok((visit(&santa), visit(&rsanta)))
 }
 
 #[aoc(day3, part1)]
@@ -62,15 +81,22 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse(">")), 2);
-        assert_eq!(part1(&parse("^>v<")), 4);
-        assert_eq!(part1(&parse("^v^v^v^v^v")), 2);
+        assert_eq!(part1(&parse(">").unwrap()), 2);
+        assert_eq!(part1(&parse("^>v<").unwrap()), 4);
+        assert_eq!(part1(&parse("^v^v^v^v^v").unwrap()), 2);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse2("^v")), 3);
-        assert_eq!(part2(&parse2("^>v<")), 3);
-        assert_eq!(part2(&parse2("^v^v^v^v^v")), 11);
+        assert_eq!(part2(&parse2("^v").unwrap()), 3);
+        assert_eq!(part2(&parse2("^>v<").unwrap()), 3);
+        assert_eq!(part2(&parse2("^v^v^v^v^v").unwrap()), 11);
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = parse2("^>x<").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 3));
+        assert_eq!(error.found(), "x");
     }
 }
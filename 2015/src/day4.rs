@@ -1,35 +1,149 @@
+use std::{
+    num::NonZeroUsize,
+    sync::atomic::{AtomicU64, Ordering},
+    thread,
+};
+
 use aoc_runner_derive::aoc;
-use md5::compute;
+use md5::{Context, Digest};
 
-#[aoc(day4, part1)]
-fn part1(input: &str) -> u32 {
-    let mut counter = 0;
+/// How many counters a worker takes at a time
+const CHUNK: u64 = 4096;
+
+/// The number of leading zero hex digits of a digest
+fn leading_zeros(digest: &Digest) -> u32 {
+    let bits: u32 = match digest.0.iter().position(|&byte| byte != 0) {
+        Some(i) => 8 * i as u32 + digest.0[i].leading_zeros(),
+        None => 128,
+    };
+    bits / 4
+}
 
+/// Writes `n` in decimal at the end of `buffer`, returning the digits
+fn format_counter(mut n: u64, buffer: &mut [u8; 20]) -> &[u8] {
+    let mut start = buffer.len();
     loop {
-        let next = input.to_string() + &counter.to_string();
-        let hash = compute(&next);
-        if hash[0..2] == [0, 0] && hash[2] <= 0x10 {
-            // println!("Test: {} Hash: {:x}", next, hash);
-            return counter;
+        start -= 1;
+        buffer[start] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            return &buffer[start..];
         }
-        counter += 1;
     }
 }
 
-#[aoc(day4, part2)]
-fn part2(input: &str) -> u32 {
-    let mut counter = 0;
+/// Looks for the counters that, written after a secret, give MD5 digests starting with
+/// `zeros` zero hex digits. The secret is hashed once and its state reused for every counter.
+#[derive(Clone)]
+pub struct Miner {
+    secret: Context,
+    zeros: u32,
+    threads: usize,
+}
 
-    loop {
-        let next = input.to_string() + &counter.to_string();
-        let hash = compute(&next);
-        if hash[0..3] == [0, 0, 0] {
-            return counter;
+impl Miner {
+    pub fn new(secret: &str, zeros: u32) -> Self {
+        let mut context = Context::new();
+        context.consume(secret);
+        Miner {
+            secret: context,
+            zeros,
+            threads: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+        }
+    }
+
+    /// How many threads search at once, at least one
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    fn hashes_to_match(&self, counter: u64, buffer: &mut [u8; 20]) -> bool {
+        let mut context = self.secret.clone();
+        context.consume(format_counter(counter, buffer));
+        leading_zeros(&context.finalize()) >= self.zeros
+    }
+
+    pub fn is_match(&self, counter: u64) -> bool {
+        self.hashes_to_match(counter, &mut [0; 20])
+    }
+
+    /// The smallest matching counter from `start` on. Workers take chunks of counters in
+    /// increasing order and stop once every chunk before the best match found so far is done,
+    /// so the result is the smallest whichever worker finds what first.
+    pub fn find_from(&self, start: u64) -> Option<u64> {
+        // Digests have 32 hex digits
+        if self.zeros > 32 {
+            return None;
+        }
+
+        let next_chunk = AtomicU64::new(start);
+        let best = AtomicU64::new(u64::MAX);
+        let worker = || {
+            let mut buffer = [0; 20];
+            loop {
+                let chunk = next_chunk.fetch_add(CHUNK, Ordering::Relaxed);
+                if chunk >= best.load(Ordering::Relaxed) || chunk < start {
+                    return;
+                }
+                for counter in chunk..chunk.saturating_add(CHUNK) {
+                    if self.hashes_to_match(counter, &mut buffer) {
+                        best.fetch_min(counter, Ordering::Relaxed);
+                        break;
+                    }
+                }
+            }
+        };
+
+        thread::scope(|scope| {
+            for _ in 1..self.threads {
+                scope.spawn(worker);
+            }
+            worker();
+        });
+
+        // The one counter `best` cannot tell apart from no match at all
+        match best.into_inner() {
+            u64::MAX if !self.is_match(u64::MAX) => None,
+            found => Some(found),
+        }
+    }
+
+    /// Every matching counter from `start` on, in order
+    pub fn matches(&self, start: u64) -> Matches<'_> {
+        Matches {
+            miner: self,
+            next: Some(start),
         }
-        counter += 1;
     }
 }
 
+#[derive(Clone)]
+pub struct Matches<'a> {
+    miner: &'a Miner,
+    next: Option<u64>,
+}
+
+impl Iterator for Matches<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let found = self.miner.find_from(self.next?);
+        self.next = found.and_then(|found| found.checked_add(1));
+        found
+    }
+}
+
+#[aoc(day4, part1)]
+fn part1(input: &str) -> u64 {
+    Miner::new(input, 5).find_from(0).unwrap()
+}
+
+#[aoc(day4, part2)]
+fn part2(input: &str) -> u64 {
+    Miner::new(input, 6).find_from(0).unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -39,4 +153,46 @@ mod tests {
         assert_eq!(part1("abcdef"), 609043);
         assert_eq!(part1("pqrstuv"), 1048970);
     }
+
+    #[test]
+    fn test_leading_zeros() {
+        let digest = |prefix: &[u8]| {
+            let mut digest = Digest([0xff; 16]);
+            digest.0[..prefix.len()].copy_from_slice(prefix);
+            digest
+        };
+        assert_eq!(leading_zeros(&digest(&[0, 0, 0x0f])), 5);
+        // Five zeros need the third byte below 0x10, not up to it
+        assert_eq!(leading_zeros(&digest(&[0, 0, 0x10])), 4);
+        assert_eq!(leading_zeros(&digest(&[0x01])), 1);
+        assert_eq!(leading_zeros(&Digest([0; 16])), 32);
+
+        let mut buffer = [0; 20];
+        assert_eq!(format_counter(0, &mut buffer), b"0");
+        assert_eq!(format_counter(609043, &mut buffer), b"609043");
+        assert_eq!(
+            format_counter(u64::MAX, &mut buffer),
+            u64::MAX.to_string().as_bytes()
+        );
+    }
+
+    #[test]
+    fn test_smallest_match() {
+        // About one counter in 4096 matches, one a chunk, so different workers find them
+        let expected: Vec<u64> = (0..)
+            .filter(|counter| {
+                let digest = md5::compute(format!("abcdef{counter}"));
+                digest.0[0] == 0 && digest.0[1] >> 4 == 0
+            })
+            .take(30)
+            .collect();
+
+        for threads in [1, 3, 8] {
+            let miner = Miner::new("abcdef", 3).threads(threads);
+            let found: Vec<u64> = miner.matches(0).take(30).collect();
+            assert_eq!(found, expected, "{threads} threads");
+            assert_eq!(miner.find_from(expected[4] + 1), Some(expected[5]));
+        }
+        assert_eq!(Miner::new("abcdef", 33).matches(0).next(), None);
+    }
 }
@@ -1,11 +1,10 @@
-use std::{
-    collections::{HashMap, HashSet},
-    fmt::Display,
-};
+use std::fmt::Display;
 
+use advent_of_code_common::parse::{lines, Cursor, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
-use regex::Regex;
+use grid::Grid;
 
+#[derive(Debug, Clone, Copy)]
 enum Action {
     TurnOn,
     TurnOff,
@@ -23,19 +22,10 @@ impl Display for Action {
     }
 }
 
-impl From<&str> for Action {
-    fn from(s: &str) -> Self {
-        match s {
-            "turn on" => Action::TurnOn,
-            "turn off" => Action::TurnOff,
-            _ => Action::Toggle,
-        }
-    }
-}
-
 type Coordinate = (u32, u32);
 
-struct Instruction {
+#[derive(Debug)]
+pub struct Instruction {
     action: Action,
     start: Coordinate,
     end: Coordinate,
@@ -51,107 +41,187 @@ impl Display for Instruction {
     }
 }
 
-#[aoc_generator(day6)]
-fn parse(input: &str) -> Vec<Instruction> {
-    let re = Regex::new(
-        r"(?P<action>turn off|turn on|toggle) (?P<startx>[\d]+),(?P<starty>[\d]+) through (?P<endx>[\d]+),(?P<endy>[\d]+)",
-    )
-    .unwrap();
+fn coordinate(line: &mut Cursor) -> Result<Coordinate, ParseError> {
+    let x = line.number()?;
+    line.literal(",")?;
+    Ok((x, line.number()?))
+}
 
+#[aoc_generator(day6)]
+pub fn parse(input: &str) -> Result<Vec<Instruction>, ParseError> {
     let mut actions = vec![];
-    for line in input.lines() {
-        let parse = re.captures(line).unwrap();
-
-        let action = Instruction {
-            action: parse["action"].into(),
-            start: (
-                parse["startx"].parse().unwrap(),
-                parse["starty"].parse().unwrap(),
-            ),
-            end: (
-                parse["endx"].parse().unwrap(),
-                parse["endy"].parse().unwrap(),
-            ),
-        };
-        actions.push(action);
+    for mut line in lines(input) {
+        let action = line.one_of(&[
+            ("turn off ", Action::TurnOff),
+            ("turn on ", Action::TurnOn),
+            ("toggle ", Action::Toggle),
+        ])?;
+        let start = coordinate(&mut line)?;
+        line.literal(" through ")?;
+        let end = coordinate(&mut line)?;
+        line.end()?;
+
+        actions.push(Instruction { action, start, end });
     }
 
-    actions
+    Ok(actions)
 }
 
-#[aoc(day6, part1)]
-fn part1(input: &[Instruction]) -> u32 {
-    let mut grid = HashSet::new();
-    for i in input {
-        match i.action {
-            Action::TurnOn => {
-                for x in i.start.0..i.end.0 + 1 {
-                    for y in i.start.1..i.end.1 + 1 {
-                        grid.insert((x, y));
-                    }
-                }
-            }
-            Action::TurnOff => {
-                for x in i.start.0..i.end.0 + 1 {
-                    for y in i.start.1..i.end.1 + 1 {
-                        grid.remove(&(x, y));
-                    }
-                }
-            }
-            Action::Toggle => {
-                for x in i.start.0..i.end.0 + 1 {
-                    for y in i.start.1..i.end.1 + 1 {
-                        let point = (x, y);
-                        if grid.contains(&point) {
-                            grid.remove(&point);
-                        } else {
-                            grid.insert(point);
-                        }
-                    }
-                }
-            }
+/// What an instruction does to one light, and how much a light counts towards the answer
+trait Semantics {
+    type Light: Copy + Default;
+
+    fn apply(action: Action, light: Self::Light) -> Self::Light;
+    fn value(light: Self::Light) -> u64;
+}
+
+struct OnOff;
+
+impl Semantics for OnOff {
+    type Light = bool;
+
+    fn apply(action: Action, light: bool) -> bool {
+        match action {
+            Action::TurnOn => true,
+            Action::TurnOff => false,
+            Action::Toggle => !light,
         }
     }
 
-    grid.len() as u32
+    fn value(light: bool) -> u64 {
+        light.into()
+    }
 }
 
-#[aoc(day6, part2)]
-fn part2(input: &[Instruction]) -> u32 {
-    let mut grid = HashMap::new();
-    for i in input {
-        match i.action {
-            Action::TurnOn => {
-                for x in i.start.0..i.end.0 + 1 {
-                    for y in i.start.1..i.end.1 + 1 {
-                        grid.entry((x, y)).and_modify(|v| *v += 1).or_insert(1);
-                    }
-                }
+struct Brightness;
+
+impl Semantics for Brightness {
+    type Light = u32;
+
+    fn apply(action: Action, light: u32) -> u32 {
+        match action {
+            Action::TurnOn => light + 1,
+            Action::TurnOff => light.saturating_sub(1),
+            Action::Toggle => light + 2,
+        }
+    }
+
+    fn value(light: u32) -> u64 {
+        light.into()
+    }
+}
+
+/// The grid cut along every rectangle edge, so each instruction covers whole blocks of lights
+/// that always share a state. Works for any grid size, only the blocks cost memory.
+struct Lights<S: Semantics> {
+    xs: Vec<u64>,
+    ys: Vec<u64>,
+    blocks: Vec<S::Light>,
+}
+
+impl<S: Semantics> Lights<S> {
+    fn new(instructions: &[Instruction]) -> Self {
+        let edges = |axis: fn(Coordinate) -> u32| {
+            let mut edges: Vec<u64> = instructions
+                .iter()
+                .flat_map(|i| [axis(i.start).into(), u64::from(axis(i.end)) + 1])
+                .collect();
+            edges.sort_unstable();
+            edges.dedup();
+            edges
+        };
+        let xs = edges(|(x, _)| x);
+        let ys = edges(|(_, y)| y);
+        let blocks =
+            vec![S::Light::default(); xs.len().saturating_sub(1) * ys.len().saturating_sub(1)];
+
+        Lights { xs, ys, blocks }
+    }
+
+    fn apply(&mut self, instruction: &Instruction) {
+        let index = |edges: &[u64], at: u64| edges.binary_search(&at).unwrap();
+        let columns = index(&self.xs, instruction.start.0.into())
+            ..index(&self.xs, u64::from(instruction.end.0) + 1);
+        let rows = index(&self.ys, instruction.start.1.into())
+            ..index(&self.ys, u64::from(instruction.end.1) + 1);
+        if columns.is_empty() {
+            return;
+        }
+
+        let width = self.xs.len() - 1;
+        for row in rows {
+            for block in &mut self.blocks[row * width..][columns.clone()] {
+                *block = S::apply(instruction.action, *block);
             }
-            Action::TurnOff => {
-                for x in i.start.0..i.end.0 + 1 {
-                    for y in i.start.1..i.end.1 + 1 {
-                        grid.entry((x, y))
-                            .and_modify(|v| {
-                                if *v > 0 {
-                                    *v -= 1
-                                }
-                            })
-                            .or_insert(0);
-                    }
-                }
+        }
+    }
+
+    fn total(&self) -> u64 {
+        let width = self.xs.len().saturating_sub(1);
+        self.blocks
+            .iter()
+            .enumerate()
+            .map(|(i, &light)| {
+                let (column, row) = (i % width, i / width);
+                S::value(light)
+                    * (self.xs[column + 1] - self.xs[column])
+                    * (self.ys[row + 1] - self.ys[row])
+            })
+            .sum()
+    }
+
+    /// The grid from `(0, 0)` to the furthest light, unlit lights black and the brightest white
+    fn picture(&self) -> Grid<u8> {
+        let width = self.xs.len().saturating_sub(1);
+        let brightest = self.blocks.iter().map(|&light| S::value(light)).max();
+        let mut picture = Grid::new(
+            self.xs.last().map_or(0, |&x| x as usize),
+            self.ys.last().map_or(0, |&y| y as usize),
+            0,
+        );
+
+        for (i, &light) in self.blocks.iter().enumerate() {
+            let level = (S::value(light) * 255 / brightest.unwrap_or(1).max(1)) as u8;
+            if level == 0 {
+                continue;
             }
-            Action::Toggle => {
-                for x in i.start.0..i.end.0 + 1 {
-                    for y in i.start.1..i.end.1 + 1 {
-                        grid.entry((x, y)).and_modify(|v| *v += 2).or_insert(2);
-                    }
+            let (column, row) = (i % width, i / width);
+            for y in self.ys[row]..self.ys[row + 1] {
+                for x in self.xs[column]..self.xs[column + 1] {
+                    picture[(x as usize, y as usize)] = level;
                 }
             }
         }
+
+        picture
+    }
+}
+
+fn lights<S: Semantics>(instructions: &[Instruction]) -> Lights<S> {
+    let mut lights = Lights::<S>::new(instructions);
+    for instruction in instructions {
+        lights.apply(instruction);
     }
+    lights
+}
+
+/// The lights once every instruction is followed, with part 2's brightness as shades of gray
+/// when `brightness` is set
+pub fn picture(instructions: &[Instruction], brightness: bool) -> Grid<u8> {
+    match brightness {
+        false => lights::<OnOff>(instructions).picture(),
+        true => lights::<Brightness>(instructions).picture(),
+    }
+}
 
-    grid.values().sum()
+#[aoc(day6, part1)]
+fn part1(input: &[Instruction]) -> u64 {
+    lights::<OnOff>(input).total()
+}
+
+#[aoc(day6, part2)]
+fn part2(input: &[Instruction]) -> u64 {
+    lights::<Brightness>(input).total()
 }
 
 #[cfg(test)]
@@ -160,16 +230,91 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse("turn on 0,0 through 999,999\ntoggle 0,0 through 999,0\nturn off 499,499 through 500,500")), 998996);
+        assert_eq!(part1(&parse("turn on 0,0 through 999,999\ntoggle 0,0 through 999,0\nturn off 499,499 through 500,500").unwrap()), 998996);
     }
 
     #[test]
     fn part2_example() {
         assert_eq!(
-            part2(&parse(
-                "turn on 0,0 through 0,0\ntoggle 0,0 through 999,999"
-            )),
+            part2(&parse("turn on 0,0 through 0,0\ntoggle 0,0 through 999,999").unwrap()),
             2000001
         );
     }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("turn on 0,0 through 9,9\ntoggle 0,0 throgh 9,9").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 12));
+        assert_eq!(error.expected, "'through'");
+        assert_eq!(
+            error.snippet(),
+            "2 | toggle 0,0 throgh 9,9\n  |            ^ expected 'through'"
+        );
+    }
+
+    /// The light by light implementation the rectangles replaced
+    fn reference<S: Semantics>(instructions: &[Instruction], size: u32) -> u64 {
+        let mut grid = vec![S::Light::default(); (size * size) as usize];
+        for i in instructions {
+            for x in i.start.0..=i.end.0 {
+                for y in i.start.1..=i.end.1 {
+                    let light = &mut grid[(y * size + x) as usize];
+                    *light = S::apply(i.action, *light);
+                }
+            }
+        }
+        grid.into_iter().map(S::value).sum()
+    }
+
+    #[test]
+    fn test_against_reference() {
+        let mut seed = 7u32;
+        let mut random = |below: u32| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (seed >> 16) % below
+        };
+
+        let mut input = String::new();
+        for _ in 0..100 {
+            let action = ["turn on", "turn off", "toggle"][random(3) as usize];
+            let (x, y) = (random(60), random(60));
+            let end = (x + random(60 - x), y + random(60 - y));
+            input += &format!("{action} {x},{y} through {},{}\n", end.0, end.1);
+        }
+
+        let instructions = parse(&input).unwrap();
+        assert_eq!(part1(&instructions), reference::<OnOff>(&instructions, 60));
+        assert_eq!(
+            part2(&instructions),
+            reference::<Brightness>(&instructions, 60)
+        );
+    }
+
+    #[test]
+    fn test_large_grid() {
+        let instructions =
+            parse("turn on 0,0 through 99999,99999\ntoggle 1,1 through 99998,99998\nturn off 9,0 through 0,9").unwrap();
+        assert_eq!(part1(&instructions), 100_000 * 100_000 - 99_998 * 99_998);
+        assert_eq!(
+            part2(&instructions),
+            100_000 * 100_000 + 2 * 99_998 * 99_998
+        );
+        assert_eq!(part1(&[]), 0);
+    }
+
+    #[test]
+    fn test_picture() {
+        let instructions =
+            parse("turn on 1,0 through 2,1\ntoggle 2,1 through 3,1\nturn off 1,0 through 1,0")
+                .unwrap();
+        assert_eq!(
+            picture(&instructions, false),
+            Grid::from_rows([[0, 0, 255, 0], [0, 255, 0, 255]])
+        );
+        assert_eq!(
+            picture(&instructions, true),
+            Grid::from_rows([[0, 0, 85, 0], [0, 85, 255, 170]])
+        );
+        assert_eq!(picture(&[], true).width(), 0);
+    }
 }
@@ -1,106 +1,568 @@
+use advent_of_code_common::parse::{lines, Cursor, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
-use regex::Regex;
-use std::collections::HashMap;
+use std::{collections::HashMap, error::Error, fmt, fmt::Write as _};
 
+/// A gate input: either another wire, by id, or a constant signal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Wire(usize),
+    Signal(u16),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gate {
+    Pass(Source),
+    Not(Source),
+    And(Source, Source),
+    Or(Source, Source),
+    LShift(Source, u16),
+    RShift(Source, u16),
+}
+
+impl Gate {
+    pub fn inputs(&self) -> impl Iterator<Item = usize> {
+        let (left, right) = match *self {
+            Gate::Pass(s) | Gate::Not(s) | Gate::LShift(s, _) | Gate::RShift(s, _) => (s, None),
+            Gate::And(l, r) | Gate::Or(l, r) => (l, Some(r)),
+        };
+
+        [Some(left), right]
+            .into_iter()
+            .flatten()
+            .filter_map(|s| match s {
+                Source::Wire(id) => Some(id),
+                Source::Signal(_) => None,
+            })
+    }
+
+    fn eval(&self, values: &[u16]) -> u16 {
+        let value = |s: Source| match s {
+            Source::Wire(id) => values[id],
+            Source::Signal(signal) => signal,
+        };
+
+        match *self {
+            Gate::Pass(s) => value(s),
+            Gate::Not(s) => !value(s),
+            Gate::And(l, r) => value(l) & value(r),
+            Gate::Or(l, r) => value(l) | value(r),
+            Gate::LShift(s, n) => value(s).checked_shl(n.into()).unwrap_or(0),
+            Gate::RShift(s, n) => value(s).checked_shr(n.into()).unwrap_or(0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CircuitError {
+    Parse(ParseError),
+    /// The wires of a loop, in signal order and ending where it started
+    Loop(Vec<String>),
+    UnknownWire(String),
+}
+
+impl fmt::Display for CircuitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CircuitError::Parse(e) => e.fmt(f),
+            CircuitError::Loop(wires) => write!(f, "combinational loop {}", wires.join(" -> ")),
+            CircuitError::UnknownWire(wire) => write!(f, "no wire named '{wire}'"),
+        }
+    }
+}
+
+impl Error for CircuitError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CircuitError::Parse(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for CircuitError {
+    fn from(error: ParseError) -> Self {
+        CircuitError::Parse(error)
+    }
+}
+
+/// A netlist compiled to interned wire ids, each driven by one gate, in evaluation order
 #[derive(Debug, Clone)]
-enum Op {
-    RShift(String, u16),
-    LShift(String, u16),
-    Not(String),
-    And(String, String),
-    Or(String, String),
-    Signal(String),
+pub struct Circuit {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+    gates: Vec<Gate>,
+    order: Vec<usize>,
+}
+
+impl Circuit {
+    pub fn id(&self, wire: &str) -> Result<usize, CircuitError> {
+        self.ids
+            .get(wire)
+            .copied()
+            .ok_or_else(|| CircuitError::UnknownWire(wire.to_string()))
+    }
+
+    /// Every wire's signal, with the `overrides` replacing what their gates would drive
+    pub fn simulate(&self, overrides: &[(&str, u16)]) -> Result<Signals<'_>, CircuitError> {
+        let mut forced = vec![None; self.names.len()];
+        for &(wire, signal) in overrides {
+            forced[self.id(wire)?] = Some(signal);
+        }
+
+        let mut values = vec![0; self.names.len()];
+        for &id in &self.order {
+            values[id] = forced[id].unwrap_or_else(|| self.gates[id].eval(&values));
+        }
+
+        Ok(Signals {
+            circuit: self,
+            values,
+        })
+    }
+
+    /// The gate driving `id`, written as in the netlist
+    fn describe(&self, id: usize) -> String {
+        let source = |s: Source| match s {
+            Source::Wire(input) => self.names[input].clone(),
+            Source::Signal(signal) => signal.to_string(),
+        };
+
+        match self.gates[id] {
+            Gate::Pass(s) => source(s),
+            Gate::Not(s) => format!("NOT {}", source(s)),
+            Gate::And(l, r) => format!("{} AND {}", source(l), source(r)),
+            Gate::Or(l, r) => format!("{} OR {}", source(l), source(r)),
+            Gate::LShift(s, n) => format!("{} LSHIFT {n}", source(s)),
+            Gate::RShift(s, n) => format!("{} RSHIFT {n}", source(s)),
+        }
+    }
+
+    /// Graphviz source with a node per gate and an edge per wire, labelled with its signal when given
+    pub fn to_dot(&self, signals: Option<&Signals>) -> String {
+        let mut dot = String::from("digraph circuit {\n    rankdir=LR;\n    node [shape=box];\n");
+        let mut used = vec![false; self.names.len()];
+        let mut constants = 0;
+
+        let label = |id: usize| match signals {
+            Some(signals) => format!("{} = {}", self.names[id], signals.values[id]),
+            None => self.names[id].clone(),
+        };
+
+        for &id in &self.order {
+            let gate = match self.gates[id] {
+                Gate::Pass(Source::Signal(signal)) => signal.to_string(),
+                Gate::Pass(_) => "=".to_string(),
+                Gate::Not(_) => "NOT".to_string(),
+                Gate::And(..) => "AND".to_string(),
+                Gate::Or(..) => "OR".to_string(),
+                Gate::LShift(_, n) => format!("LSHIFT {n}"),
+                Gate::RShift(_, n) => format!("RSHIFT {n}"),
+            };
+            writeln!(dot, "    g{id} [label=\"{gate}\"];").unwrap();
+
+            let sources = match self.gates[id] {
+                Gate::Pass(Source::Signal(_)) => vec![],
+                Gate::Pass(s) | Gate::Not(s) | Gate::LShift(s, _) | Gate::RShift(s, _) => vec![s],
+                Gate::And(l, r) | Gate::Or(l, r) => vec![l, r],
+            };
+            for source in sources {
+                match source {
+                    Source::Wire(input) => {
+                        used[input] = true;
+                        writeln!(dot, "    g{input} -> g{id} [label=\"{}\"];", label(input))
+                    }
+                    Source::Signal(signal) => {
+                        constants += 1;
+                        writeln!(
+                            dot,
+                            "    c{constants} [label=\"{signal}\", shape=plaintext];\n    c{constants} -> g{id};"
+                        )
+                    }
+                }
+                .unwrap();
+            }
+        }
+
+        // Wires no gate reads still get an edge, towards a node for the output
+        for &id in self.order.iter().filter(|&&id| !used[id]) {
+            writeln!(
+                dot,
+                "    o{id} [label=\"{}\", shape=plaintext];\n    g{id} -> o{id} [label=\"{}\"];",
+                self.names[id],
+                label(id)
+            )
+            .unwrap();
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// The gates that differ in `other`, matching wires by name, and the signals that changed with them
+    pub fn diff(&self, other: &Circuit) -> Diff {
+        let mut diff = Diff::default();
+
+        for &id in &self.order {
+            if !other.ids.contains_key(&self.names[id]) {
+                diff.gates
+                    .push((self.names[id].clone(), Some(self.describe(id)), None));
+            }
+        }
+
+        let before = self.simulate(&[]).unwrap();
+        let after = other.simulate(&[]).unwrap();
+
+        for &id in &other.order {
+            let wire = &other.names[id];
+            let gate = other.describe(id);
+
+            match self.ids.get(wire) {
+                None => diff.gates.push((wire.clone(), None, Some(gate))),
+                Some(&old) => {
+                    let previous = self.describe(old);
+                    if previous != gate {
+                        diff.gates.push((wire.clone(), Some(previous), Some(gate)));
+                    }
+                    if before.values[old] != after.values[id] {
+                        diff.values
+                            .push((wire.clone(), before.values[old], after.values[id]));
+                    }
+                }
+            }
+        }
+
+        diff
+    }
+}
+
+/// What changed between two circuits, wires listed in evaluation order
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Diff {
+    /// Wires whose gate changed, as written in each netlist, `None` where the wire doesn't exist
+    pub gates: Vec<(String, Option<String>, Option<String>)>,
+    /// Wires in both circuits whose signal changed
+    pub values: Vec<(String, u16, u16)>,
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.gates.is_empty() && self.values.is_empty()
+    }
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (wire, before, after) in &self.gates {
+            match (before, after) {
+                (Some(before), Some(after)) => writeln!(f, "~ {wire}: {before} => {after}")?,
+                (Some(before), None) => writeln!(f, "- {wire}: {before}")?,
+                (None, Some(after)) => writeln!(f, "+ {wire}: {after}")?,
+                (None, None) => {}
+            }
+        }
+
+        for (wire, before, after) in &self.values {
+            writeln!(f, "  {wire}: {before} -> {after}")?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct Signals<'a> {
+    circuit: &'a Circuit,
+    values: Vec<u16>,
+}
+
+impl Signals<'_> {
+    pub fn get(&self, wire: &str) -> Result<u16, CircuitError> {
+        Ok(self.values[self.circuit.id(wire)?])
+    }
+}
+
+#[derive(Default)]
+struct Netlist {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+    gates: Vec<Option<(usize, Gate)>>,
+    first_use: Vec<Option<ParseError>>,
+}
+
+impl Netlist {
+    fn intern(&mut self, wire: &str) -> usize {
+        if let Some(&id) = self.ids.get(wire) {
+            return id;
+        }
+
+        self.names.push(wire.to_string());
+        self.ids.insert(wire.to_string(), self.names.len() - 1);
+        self.gates.push(None);
+        self.first_use.push(None);
+        self.names.len() - 1
+    }
+
+    fn source(&mut self, line: &mut Cursor) -> Result<Source, ParseError> {
+        if line.rest().starts_with(|c: char| c.is_ascii_digit()) {
+            return Ok(Source::Signal(line.number()?));
+        }
+
+        let at = line.clone();
+        let id = self.intern(line.word()?);
+        self.first_use[id].get_or_insert_with(|| at.error("a wire driven by the circuit"));
+        Ok(Source::Wire(id))
+    }
+
+    fn gate(&mut self, line: &mut Cursor) -> Result<Gate, ParseError> {
+        if line.eat("NOT ") {
+            line.skip_whitespace();
+            return Ok(Gate::Not(self.source(line)?));
+        }
+
+        let left = self.source(line)?;
+        line.skip_whitespace();
+
+        let operator = line.clone();
+        let name = line.take_while(|c| c.is_ascii_uppercase());
+        line.skip_whitespace();
+        Ok(match name {
+            "" => Gate::Pass(left),
+            "AND" => Gate::And(left, self.source(line)?),
+            "OR" => Gate::Or(left, self.source(line)?),
+            "LSHIFT" => Gate::LShift(left, line.number()?),
+            "RSHIFT" => Gate::RShift(left, line.number()?),
+            _ => return Err(operator.error("one of 'AND', 'OR', 'LSHIFT', 'RSHIFT', '->'")),
+        })
+    }
+
+    fn compile(self) -> Result<Circuit, CircuitError> {
+        let mut gates = Vec::with_capacity(self.gates.len());
+        for (gate, first_use) in self.gates.into_iter().zip(self.first_use) {
+            match (gate, first_use) {
+                (Some((_, gate)), _) => gates.push(gate),
+                (None, Some(error)) => return Err(error.into()),
+                (None, None) => unreachable!("wires are interned when driven or used"),
+            }
+        }
+
+        let order = sort(&gates).map_err(|cycle| {
+            CircuitError::Loop(cycle.into_iter().map(|id| self.names[id].clone()).collect())
+        })?;
+
+        Ok(Circuit {
+            names: self.names,
+            ids: self.ids,
+            gates,
+            order,
+        })
+    }
+}
+
+/// Kahn's algorithm, or the ids of a loop when some wires can never be evaluated
+fn sort(gates: &[Gate]) -> Result<Vec<usize>, Vec<usize>> {
+    let mut pending = vec![0; gates.len()];
+    let mut users = vec![vec![]; gates.len()];
+    for (id, gate) in gates.iter().enumerate() {
+        for input in gate.inputs() {
+            pending[id] += 1;
+            users[input].push(id);
+        }
+    }
+
+    let mut ready: Vec<usize> = (0..gates.len()).filter(|&id| pending[id] == 0).collect();
+    let mut order = Vec::with_capacity(gates.len());
+    while let Some(id) = ready.pop() {
+        order.push(id);
+        for &user in &users[id] {
+            pending[user] -= 1;
+            if pending[user] == 0 {
+                ready.push(user);
+            }
+        }
+    }
+
+    if order.len() == gates.len() {
+        return Ok(order);
+    }
+
+    // Every wire left waits on another one left, so walking back through them must cycle
+    let mut path = vec![];
+    let mut position = vec![None; gates.len()];
+    let mut id = (0..gates.len()).find(|&id| pending[id] > 0).unwrap();
+    while position[id].is_none() {
+        position[id] = Some(path.len());
+        path.push(id);
+        id = gates[id]
+            .inputs()
+            .find(|&input| pending[input] > 0)
+            .unwrap();
+    }
+
+    let mut cycle = path.split_off(position[id].unwrap());
+    cycle.reverse();
+    cycle.push(cycle[0]);
+    Err(cycle)
 }
 
 #[aoc_generator(day7)]
-fn parse(input: &str) -> HashMap<String, Op> {
-    let mut circuit = HashMap::new();
-    let reline = Regex::new(r"^(?P<operand>.+) -> (?P<wire>[\w]+)$").unwrap();
-
-    for line in input.lines() {
-        let capture = reline.captures(line).unwrap();
-        let tag = capture["wire"].to_owned();
-        let operand = capture["operand"].to_owned();
-
-        if operand.contains("NOT") {
-            circuit.insert(tag, Op::Not(operand[4..].to_owned()));
-        } else if operand.contains("AND") {
-            let values: Vec<&str> = operand.split(" AND ").collect();
-            circuit.insert(tag, Op::And(values[0].to_owned(), values[1].to_owned()));
-        } else if operand.contains("OR") {
-            let values: Vec<&str> = operand.split(" OR ").collect();
-            circuit.insert(tag, Op::Or(values[0].to_owned(), values[1].to_owned()));
-        } else if operand.contains("LSHIFT") {
-            let values: Vec<&str> = operand.split(" LSHIFT ").collect();
-            circuit.insert(
-                tag,
-                Op::LShift(values[0].to_owned(), values[1].parse().unwrap()),
-            );
-        } else if operand.contains("RSHIFT") {
-            let values: Vec<&str> = operand.split(" RSHIFT ").collect();
-            circuit.insert(
-                tag,
-                Op::RShift(values[0].to_owned(), values[1].parse().unwrap()),
-            );
-        } else {
-            circuit.insert(tag, Op::Signal(operand));
-        }
-    }
-    circuit
-}
-
-fn resolver(input: &mut HashMap<String, Op>, tag: &str) -> u16 {
-    let operation = &input.get_mut(tag).cloned();
-    let result = if let Some(operation) = operation {
-        match operation {
-            Op::And(l, r) => resolver(input, l) & resolver(input, r),
-            Op::Or(l, r) => resolver(input, l) | resolver(input, r),
-            Op::Not(v) => !resolver(input, v),
-            Op::RShift(v, shift) => resolver(input, v) >> *shift,
-            Op::LShift(v, shift) => resolver(input, v) << *shift,
-            Op::Signal(v) => resolver(input, v),
-        }
-    } else {
-        tag.parse().unwrap()
-    };
-    input
-        .entry(tag.to_string())
-        .and_modify(|v| *v = Op::Signal(result.to_string()));
-    result
+pub fn parse(input: &str) -> Result<Circuit, CircuitError> {
+    let mut netlist = Netlist::default();
+
+    for mut line in lines(input) {
+        let gate = netlist.gate(&mut line)?;
+
+        line.skip_whitespace();
+        line.literal("->")?;
+        line.skip_whitespace();
+        let output = line.clone();
+        let id = netlist.intern(line.word()?);
+        line.end()?;
+
+        if let Some((first, _)) = netlist.gates[id] {
+            return Err(output
+                .error(format!("a wire not already driven on line {first}"))
+                .into());
+        }
+        netlist.gates[id] = Some((line.line(), gate));
+    }
+
+    netlist.compile()
 }
 
 #[aoc(day7, part1)]
-fn part1(input: &HashMap<String, Op>) -> u16 {
-    resolver(&mut input.clone(), "a")
+fn part1(circuit: &Circuit) -> Result<u16, CircuitError> {
+    circuit.simulate(&[])?.get("a")
 }
 
 #[aoc(day7, part2)]
-fn part2(input: &HashMap<String, Op>) -> u16 {
-    let mut input = input.clone();
-    input
-        .entry("b".to_owned())
-        .and_modify(|v| *v = Op::Signal("46065".to_owned()));
-    resolver(&mut input, "a")
+fn part2(circuit: &Circuit) -> Result<u16, CircuitError> {
+    let a = part1(circuit)?;
+    circuit.simulate(&[("b", a)])?.get("a")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "123 -> x\n456 -> y\nx AND y -> d\nx OR y -> e\nx LSHIFT 2 -> f\ny RSHIFT 2 -> g\nNOT x -> h\nNOT y -> i";
+
     #[test]
     fn part1_example() {
-        assert_eq!(resolver(&mut parse("123 -> x\n456 -> y\nx AND y -> d\nx OR y -> e\nx LSHIFT 2 -> f\ny RSHIFT 2 -> g\nNOT x -> h\nNOT y -> i"), "d"), 72);
-        assert_eq!(resolver(&mut parse("123 -> x\n456 -> y\nx AND y -> d\nx OR y -> e\nx LSHIFT 2 -> f\ny RSHIFT 2 -> g\nNOT x -> h\nNOT y -> i"), "e"), 507);
-        assert_eq!(resolver(&mut parse("123 -> x\n456 -> y\nx AND y -> d\nx OR y -> e\nx LSHIFT 2 -> f\ny RSHIFT 2 -> g\nNOT x -> h\nNOT y -> i"), "f"), 492);
-        assert_eq!(resolver(&mut parse("123 -> x\n456 -> y\nx AND y -> d\nx OR y -> e\nx LSHIFT 2 -> f\ny RSHIFT 2 -> g\nNOT x -> h\nNOT y -> i"), "g"), 114);
-        assert_eq!(resolver(&mut parse("123 -> x\n456 -> y\nx AND y -> d\nx OR y -> e\nx LSHIFT 2 -> f\ny RSHIFT 2 -> g\nNOT x -> h\nNOT y -> i"), "h"), 65412);
-        assert_eq!(resolver(&mut parse("123 -> x\n456 -> y\nx AND y -> d\nx OR y -> e\nx LSHIFT 2 -> f\ny RSHIFT 2 -> g\nNOT x -> h\nNOT y -> i"), "i"), 65079);
-        assert_eq!(resolver(&mut parse("123 -> x\n456 -> y\nx AND y -> d\nx OR y -> e\nx LSHIFT 2 -> f\ny RSHIFT 2 -> g\nNOT x -> h\nNOT y -> i"), "x"), 123);
-        assert_eq!(resolver(&mut parse("123 -> x\n456 -> y\nx AND y -> d\nx OR y -> e\nx LSHIFT 2 -> f\ny RSHIFT 2 -> g\nNOT x -> h\nNOT y -> i"), "y"), 456);
-    }
-
-    // #[test]
-    // fn part2_example() {
-    //     assert_eq!(part2(&parse("<EXAMPLE>")), "<RESULT>");
-    // }
+        let circuit = parse(EXAMPLE).unwrap();
+        let signals = circuit.simulate(&[]).unwrap();
+        assert_eq!(signals.get("d"), Ok(72));
+        assert_eq!(signals.get("e"), Ok(507));
+        assert_eq!(signals.get("f"), Ok(492));
+        assert_eq!(signals.get("g"), Ok(114));
+        assert_eq!(signals.get("h"), Ok(65412));
+        assert_eq!(signals.get("i"), Ok(65079));
+        assert_eq!(signals.get("x"), Ok(123));
+        assert_eq!(signals.get("y"), Ok(456));
+        assert_eq!(
+            signals.get("a"),
+            Err(CircuitError::UnknownWire("a".to_string()))
+        );
+    }
+
+    #[test]
+    fn part2_example() {
+        let circuit = parse("d -> a\n3 -> b\nb LSHIFT 1 -> c\nb OR c -> d").unwrap();
+        assert_eq!(part1(&circuit), Ok(7));
+        assert_eq!(part2(&circuit), Ok(15));
+    }
+
+    #[test]
+    fn test_overrides() {
+        let circuit = parse(EXAMPLE).unwrap();
+        let signals = circuit.simulate(&[("x", 1), ("f", 9)]).unwrap();
+        assert_eq!(signals.get("d"), Ok(0));
+        assert_eq!(signals.get("f"), Ok(9));
+        assert_eq!(signals.get("h"), Ok(65534));
+        assert!(circuit.simulate(&[("z", 1)]).is_err());
+
+        let circuit = parse("1 -> x\nx LSHIFT 16 -> y").unwrap();
+        assert_eq!(circuit.simulate(&[]).unwrap().get("y"), Ok(0));
+    }
+
+    #[test]
+    fn test_loops() {
+        let error = parse("1 -> a\nc AND a -> b\nb -> c\nc -> d").unwrap_err();
+        assert_eq!(error.to_string(), "combinational loop b -> c -> b");
+
+        let error = parse("x OR x -> x").unwrap_err();
+        assert_eq!(error, CircuitError::Loop(vec!["x".into(), "x".into()]));
+    }
+
+    #[test]
+    fn parse_error() {
+        let Err(CircuitError::Parse(error)) = parse("123 -> x\nx XOR y -> z") else {
+            panic!("XOR isn't a gate")
+        };
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(error.found(), "XOR");
+
+        let Err(CircuitError::Parse(error)) = parse("123 -> x\nNOT x ->") else {
+            panic!("the output is missing")
+        };
+        assert_eq!((error.line, error.column), (2, 9));
+        assert_eq!(error.expected, "word");
+
+        let Err(CircuitError::Parse(error)) = parse("123 -> x\nx AND zz -> y\nzz -> a") else {
+            panic!("zz is never driven")
+        };
+        assert_eq!((error.line, error.column), (2, 7));
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 7: expected a wire driven by the circuit, found 'zz'"
+        );
+
+        let Err(CircuitError::Parse(error)) = parse("1 -> x\n2 -> x") else {
+            panic!("x has two drivers")
+        };
+        assert_eq!((error.line, error.column), (2, 6));
+        assert_eq!(error.expected, "a wire not already driven on line 1");
+
+        assert!(parse("70000 -> x").is_err());
+    }
+
+    #[test]
+    fn test_dot() {
+        let circuit = parse("3 -> x\nx LSHIFT 1 -> y\n1 AND y -> a").unwrap();
+        assert_eq!(
+            circuit.to_dot(None),
+            r#"digraph circuit {
+    rankdir=LR;
+    node [shape=box];
+    g0 [label="3"];
+    g1 [label="LSHIFT 1"];
+    g0 -> g1 [label="x"];
+    g2 [label="AND"];
+    c1 [label="1", shape=plaintext];
+    c1 -> g2;
+    g1 -> g2 [label="y"];
+    o2 [label="a", shape=plaintext];
+    g2 -> o2 [label="a"];
+}
+"#
+        );
+
+        let signals = circuit.simulate(&[]).unwrap();
+        let dot = circuit.to_dot(Some(&signals));
+        assert!(dot.contains(r#"g1 -> g2 [label="y = 6"];"#));
+        assert!(dot.contains(r#"g2 -> o2 [label="a = 0"];"#));
+    }
+
+    #[test]
+    fn test_diff() {
+        let before = parse(EXAMPLE).unwrap();
+        let after = parse("123 -> x\n456 -> y\nx OR y -> d\nx OR y -> e\nd LSHIFT 2 -> f\ny RSHIFT 2 -> g\nNOT x -> h\nNOT f -> j").unwrap();
+
+        let diff = after.diff(&after);
+        assert!(diff.is_empty());
+
+        let diff = before.diff(&after);
+        assert_eq!(
+            diff.to_string(),
+            "- i: NOT y\n~ d: x AND y => x OR y\n~ f: x LSHIFT 2 => d LSHIFT 2\n+ j: NOT f\n  d: 72 -> 507\n  f: 492 -> 2028\n"
+        );
+    }
 }
@@ -1,66 +1,94 @@
+use advent_of_code_common::parse::{lines, Cursor, ParseError};
 use aoc_runner_derive::aoc;
-fn count_chars(line: &str) -> u32 {
-    let line = &line[1..line.len() - 1];
-    let mut counter = 0;
-    let mut index = 0;
-    while index < line.len() {
-        let c = line.as_bytes()[index] as char;
-        if c == '\\' && index < line.len() - 1 {
-            let nc = line.as_bytes()[index + 1] as char;
-            if nc == '"' || nc == '\\' {
-                index += 1
-            } else if nc == 'x' && index < line.len() - 3 {
-                let first = line.as_bytes()[index + 2];
-                let second = line.as_bytes()[index + 3];
-                if first.is_ascii_hexdigit() && second.is_ascii_hexdigit() {
-                    index += 3
+
+const ESCAPE: &str = "an escape: \\\\, \\\" or \\x and two hex digits";
+
+/// Reads one quoted literal off `line`, returning the bytes it stands for
+fn decode_from(line: &mut Cursor) -> Result<Vec<u8>, ParseError> {
+    line.literal("\"")?;
+    let mut bytes = vec![];
+    loop {
+        bytes.extend(line.take_while(|c| c != '"' && c != '\\').as_bytes());
+        if line.eat("\"") {
+            return Ok(bytes);
+        }
+
+        let escape = line.clone();
+        if !line.eat("\\") {
+            return Err(line.error("'\"'"));
+        }
+        if line.eat("\\") {
+            bytes.push(b'\\');
+        } else if line.eat("\"") {
+            bytes.push(b'"');
+        } else if line.eat("x") {
+            let hex = line
+                .rest()
+                .get(..2)
+                .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()));
+            match hex {
+                Some(hex) => {
+                    bytes.push(u8::from_str_radix(hex, 16).unwrap());
+                    line.eat(hex);
                 }
+                None => return Err(escape.error(ESCAPE)),
             }
+        } else {
+            return Err(escape.error(ESCAPE));
         }
-        counter += 1;
-        index += 1;
     }
+}
 
-    counter as u32
+/// The bytes a quoted literal such as `"a\"b\x27"` stands for
+pub fn decode(literal: &str) -> Result<Vec<u8>, ParseError> {
+    let mut line = Cursor::new(1, literal);
+    let bytes = decode_from(&mut line)?;
+    line.end()?;
+    Ok(bytes)
 }
 
-#[aoc(day8, part1)]
-fn part1(input: &str) -> u32 {
-    let mut acc = 0;
-    let mut total = 0;
-    for line in input.lines() {
-        total += line.len() as u32;
-        acc += count_chars(line);
+/// The quoted literal for `bytes`, escaping quotes and backslashes, and as `\xHH` whatever is
+/// not printable ASCII
+pub fn encode(bytes: &[u8]) -> String {
+    let mut literal = String::from("\"");
+    for &byte in bytes {
+        match byte {
+            b'"' => literal += "\\\"",
+            b'\\' => literal += "\\\\",
+            b' '..=b'~' => literal.push(char::from(byte)),
+            _ => literal += &format!("\\x{byte:02x}"),
+        }
     }
-
-    total - acc
+    literal.push('"');
+    literal
 }
 
-fn expand_cound_chars(line: &str) -> u32 {
-    let mut index = 0;
-    let mut counter = 2;
-    while index < line.len() {
-        let c = line.as_bytes()[index] as char;
-        if c == '\\' || c == '"' {
-            counter += 1;
-        };
-        counter += 1;
-        index += 1;
-    }
+/// Each literal of the input, one per line, with the bytes it decodes to
+fn literals(input: &str) -> Result<Vec<(&str, Vec<u8>)>, ParseError> {
+    lines(input)
+        .map(|mut line| {
+            let text = line.rest().trim_end();
+            let bytes = decode_from(&mut line)?;
+            line.end()?;
+            Ok((text, bytes))
+        })
+        .collect()
+}
 
-    counter as u32
+#[aoc(day8, part1)]
+fn part1(input: &str) -> Result<usize, ParseError> {
+    Ok(literals(input)?
+        .iter()
+        .map(|(text, bytes)| text.len() - bytes.len())
+        .sum())
 }
 
 #[aoc(day8, part2)]
-fn part2(input: &str) -> u32 {
-    let mut acc = 0;
-    let mut total = 0;
-    for line in input.lines() {
-        total += line.len() as u32;
-        acc += expand_cound_chars(line);
-    }
-
-    acc - total
+fn part2(input: &str) -> Result<usize, ParseError> {
+    Ok(literals(input)?
+        .iter()
+        .map(|(text, _)| encode(text.as_bytes()).len() - text.len())
+        .sum())
 }
 
 #[cfg(test)]
@@ -69,16 +97,61 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1("\"nnpipxufvbfpoz\\\"jno\""), 1);
-        assert_eq!(part1("\"\"\n\"abc\"\n\"aaa\\\"aaa\"\n\"\\x27\""), 12);
-        assert_eq!(part1("\"j\""), 2);
+        assert_eq!(part1("\"nnpipxufvbfpoz\\\"jno\""), Ok(3));
+        assert_eq!(part1("\"\"\n\"abc\"\n\"aaa\\\"aaa\"\n\"\\x27\""), Ok(12));
+        assert_eq!(part1("\"j\""), Ok(2));
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2("\"\""), 4);
-        assert_eq!(part2("\"abc\""), 4);
-        assert_eq!(part2("\"aaa\\\"aaa\""), 6);
-        assert_eq!(part2("\"\\x27\""), 5);
+        assert_eq!(part2("\"\""), Ok(4));
+        assert_eq!(part2("\"abc\""), Ok(4));
+        assert_eq!(part2("\"aaa\\\"aaa\""), Ok(6));
+        assert_eq!(part2("\"\\x27\""), Ok(5));
+    }
+
+    #[test]
+    fn test_codec() {
+        assert_eq!(decode(r#""aaa\"aaa""#), Ok(b"aaa\"aaa".to_vec()));
+        assert_eq!(decode(r#""\x27\\\xfF""#), Ok(vec![0x27, b'\\', 0xff]));
+        assert_eq!(encode(br#""aaa\"aaa""#), r#""\"aaa\\\"aaa\"""#);
+        assert_eq!(encode(b"\x00\n\x7f\xc3\xa9~"), r#""\x00\x0a\x7f\xc3\xa9~""#);
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = decode(r#""ab\x4""#).unwrap_err();
+        assert_eq!((error.line, error.column), (1, 4));
+        assert_eq!(error.expected, ESCAPE);
+
+        let error = part1("\"ok\"\n\"a\\qb\"").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+        let error = part1("\"unterminated\\\\").unwrap_err();
+        assert_eq!((error.column, error.expected.as_str()), (16, "'\"'"));
+        let error = decode("abc").unwrap_err();
+        assert_eq!((error.column, error.expected.as_str()), (1, "'\"'"));
+        let error = decode("\"a\" b").unwrap_err();
+        assert_eq!((error.column, error.expected.as_str()), (5, "end of line"));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut seed = 3u64;
+        for length in 0..200 {
+            let bytes: Vec<u8> = (0..length)
+                .map(|_| {
+                    seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+                    // Half of them among the bytes of the escape syntax
+                    match seed >> 63 {
+                        0 => b"\\\"x\x00"[(seed >> 40) as usize % 4],
+                        _ => (seed >> 33) as u8,
+                    }
+                })
+                .collect();
+            let literal = encode(&bytes);
+            assert_eq!(decode(&literal), Ok(bytes), "{literal}");
+        }
+        let all: Vec<u8> = (0..=255).collect();
+        assert_eq!(decode(&encode(&all)), Ok(all));
     }
 }
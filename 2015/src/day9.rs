@@ -1,115 +1,35 @@
-use std::collections::{HashMap, HashSet};
-
+use advent_of_code_common::parse::{lines, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
-use regex::Regex;
-#[aoc_generator(day9)]
-fn parse(input: &str) -> HashMap<String, HashMap<String, u32>> {
-    let re = Regex::new(r"(?P<from>[\w]+) to (?P<to>[\w]+) = (?P<distance>[\d]+)").unwrap();
-    let mut matrix = HashMap::new();
-
-    for line in input.lines() {
-        let info = re.captures(line).unwrap();
-        matrix
-            .entry(info["from"].to_string())
-            .or_insert(HashMap::new())
-            .insert(
-                info["to"].to_string(),
-                info["distance"].parse::<u32>().unwrap(),
-            );
-        matrix
-            .entry(info["to"].to_string())
-            .or_insert(HashMap::new())
-            .insert(
-                info["from"].to_string(),
-                info["distance"].parse::<u32>().unwrap(),
-            );
-    }
 
-    matrix
-}
-
-fn min_path(
-    input: &HashMap<String, HashMap<String, u32>>,
-    from: &String,
-    cities: &Vec<String>,
-    visited: &mut HashSet<String>,
-) -> u32 {
-    if visited.len() == cities.len() {
-        return 0;
-    }
-
-    visited.insert(from.to_owned());
-
-    let mut min = u32::MAX;
-    for city in cities {
-        if !visited.contains(city) {
-            let distance = input[from][city] + min_path(input, city, cities, visited);
-            if distance < min {
-                min = distance;
-            }
-        }
-    }
-    visited.remove(from);
-
-    if min < u32::MAX {
-        min
-    } else {
-        0
-    }
-}
+use crate::tsp::{solve, Goal, Graph, RouteError, Shape};
 
-fn max_path(
-    input: &HashMap<String, HashMap<String, u32>>,
-    from: &String,
-    cities: &Vec<String>,
-    visited: &mut HashSet<String>,
-) -> u32 {
-    if visited.len() == cities.len() {
-        return 0;
-    }
-
-    visited.insert(from.to_owned());
-
-    let mut max = 0;
-    for city in cities {
-        if !visited.contains(city) {
-            let distance = input[from][city] + max_path(input, city, cities, visited);
-            if distance > max {
-                max = distance;
-            }
-        }
+#[aoc_generator(day9)]
+fn parse(input: &str) -> Result<Graph, ParseError> {
+    let mut graph = Graph::default();
+
+    for mut line in lines(input) {
+        let from = line.word()?;
+        line.literal(" to ")?;
+        let to = line.word()?;
+        line.literal(" = ")?;
+        let distance: u32 = line.number()?;
+        line.end()?;
+
+        graph.set(from, to, distance.into());
+        graph.set(to, from, distance.into());
     }
-    visited.remove(from);
 
-    max
+    Ok(graph)
 }
 
 #[aoc(day9, part1)]
-fn part1(input: &HashMap<String, HashMap<String, u32>>) -> u32 {
-    let cities: Vec<String> = input.keys().cloned().collect();
-    let mut visited = HashSet::new();
-    cities
-        .iter()
-        .map(|city| min_path(input, city, &cities, &mut visited))
-        .collect::<Vec<u32>>()
-        .iter()
-        .min()
-        .unwrap()
-        .to_owned()
+fn part1(input: &Graph) -> Result<i64, RouteError> {
+    Ok(solve(input, Shape::Path, Goal::Min)?.cost)
 }
 
 #[aoc(day9, part2)]
-fn part2(input: &HashMap<String, HashMap<String, u32>>) -> u32 {
-    let cities: Vec<String> = input.keys().cloned().collect();
-    let mut visited = HashSet::new();
-    cities
-        .iter()
-        .map(|city| max_path(input, city, &cities, &mut visited))
-        .collect::<Vec<u32>>()
-        .iter()
-        .max()
-        .unwrap()
-        .to_owned()
+fn part2(input: &Graph) -> Result<i64, RouteError> {
+    Ok(solve(input, Shape::Path, Goal::Max)?.cost)
 }
 
 #[cfg(test)]
@@ -119,22 +39,41 @@ mod tests {
     #[test]
     fn part1_example() {
         assert_eq!(
-            part1(&parse(
-                "London to Dublin = 464\nLondon to Belfast = 518\nDublin to Belfast = 141"
-            )),
-            605
+            part1(
+                &parse("London to Dublin = 464\nLondon to Belfast = 518\nDublin to Belfast = 141")
+                    .unwrap()
+            ),
+            Ok(605)
         );
     }
 
     #[test]
     fn part2_example() {
         assert_eq!(
-            part2(&parse(
-                "London to Dublin = 464\nLondon to Belfast = 518\nDublin to Belfast = 141"
-            )),
-            982
+            part2(
+                &parse("London to Dublin = 464\nLondon to Belfast = 518\nDublin to Belfast = 141")
+                    .unwrap()
+            ),
+            Ok(982)
+        );
+    }
+
+    #[test]
+    fn test_missing_distance() {
+        let input = parse("London to Dublin = 464\nDublin to Belfast = 141").unwrap();
+        assert_eq!(
+            part1(&input).unwrap_err().to_string(),
+            "no edge from London to Belfast"
         );
     }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("London to Dublin = 464\nLondon to Belfast is 518").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 19));
+        assert_eq!(error.expected, "'='");
+        assert_eq!(error.found(), "is");
+    }
 }
 
 // AC, Tris, Snowdin, Faerun, Tambi, Straylight, Norrath, Straylight, Abre
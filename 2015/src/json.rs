@@ -0,0 +1,621 @@
+//! A small JSON parser, enough to walk documents without pulling in serde: values are parsed
+//! into a tree, which a `Visitor` then goes through with the path to everything it sees.
+
+use std::{error::Error, fmt::Display, str::FromStr};
+
+use advent_of_code_common::parse::ParseError;
+
+/// How deep arrays and objects may nest, so that parsing and walking cannot run out of stack
+pub const MAX_DEPTH: usize = 512;
+
+/// Integers are kept exact as long as they fit an `i64`, everything else is floating point
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Number {
+    Integer(i64),
+    Float(f64),
+}
+
+impl Number {
+    pub fn as_f64(self) -> f64 {
+        match self {
+            Number::Integer(n) => n as f64,
+            Number::Float(x) => x,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(String),
+    Array(Vec<Value>),
+    /// The members in document order, duplicate keys included
+    Object(Vec<(String, Value)>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Something else, or the end of the input, where this should be
+    Expected(&'static str),
+    InvalidNumber,
+    InvalidEscape,
+    /// A control character inside a string, where it must be escaped
+    ControlCharacter,
+    /// More than whitespace after the value
+    TrailingCharacters,
+    /// Arrays and objects nested deeper than `MAX_DEPTH`
+    TooDeep,
+}
+
+impl ErrorKind {
+    fn expected(self) -> String {
+        match self {
+            ErrorKind::Expected(expected) => expected.to_string(),
+            ErrorKind::InvalidNumber => "a number".to_string(),
+            ErrorKind::InvalidEscape => "an escape sequence".to_string(),
+            ErrorKind::ControlCharacter => "an escaped control character".to_string(),
+            ErrorKind::TrailingCharacters => "end of input".to_string(),
+            ErrorKind::TooDeep => format!("at most {MAX_DEPTH} levels of nesting"),
+        }
+    }
+}
+
+/// What went wrong, and where as a [`ParseError`] so that it is reported like any other input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonError {
+    pub kind: ErrorKind,
+    pub at: ParseError,
+}
+
+impl Display for JsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.at.fmt(f)
+    }
+}
+
+impl Error for JsonError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.at)
+    }
+}
+
+impl From<JsonError> for ParseError {
+    fn from(error: JsonError) -> Self {
+        error.at
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    at: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, kind: ErrorKind) -> JsonError {
+        let before = &self.input[..self.at];
+        let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+        let line = self.input[line_start..].lines().next().unwrap_or("");
+        JsonError {
+            kind,
+            at: ParseError::new(
+                before.matches('\n').count() + 1,
+                before[line_start..].chars().count() + 1,
+                kind.expected(),
+                line,
+            ),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.at).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.at += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8, expected: &'static str) -> Result<(), JsonError> {
+        if self.peek() != Some(byte) {
+            return Err(self.error(ErrorKind::Expected(expected)));
+        }
+        self.at += 1;
+        Ok(())
+    }
+
+    fn literal(&mut self, word: &'static str, value: Value) -> Result<Value, JsonError> {
+        if !self.input[self.at..].starts_with(word) {
+            return Err(self.error(ErrorKind::Expected("a value")));
+        }
+        self.at += word.len();
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<Value, JsonError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => self.nested(Parser::object),
+            Some(b'[') => self.nested(Parser::array),
+            Some(b'"') => self.string().map(Value::String),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(b't') => self.literal("true", Value::Bool(true)),
+            Some(b'f') => self.literal("false", Value::Bool(false)),
+            Some(b'n') => self.literal("null", Value::Null),
+            _ => Err(self.error(ErrorKind::Expected("a value"))),
+        }
+    }
+
+    fn nested(
+        &mut self,
+        parse: fn(&mut Self) -> Result<Value, JsonError>,
+    ) -> Result<Value, JsonError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(ErrorKind::TooDeep));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn array(&mut self) -> Result<Value, JsonError> {
+        self.at += 1;
+        let mut items = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.at += 1;
+            return Ok(Value::Array(items));
+        }
+
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.at += 1,
+                Some(b']') => {
+                    self.at += 1;
+                    return Ok(Value::Array(items));
+                }
+                _ => return Err(self.error(ErrorKind::Expected("',' or ']'"))),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, JsonError> {
+        self.at += 1;
+        let mut members = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.at += 1;
+            return Ok(Value::Object(members));
+        }
+
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.error(ErrorKind::Expected("a string key")));
+            }
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(b':', "':'")?;
+            members.push((key, self.value()?));
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.at += 1,
+                Some(b'}') => {
+                    self.at += 1;
+                    return Ok(Value::Object(members));
+                }
+                _ => return Err(self.error(ErrorKind::Expected("',' or '}'"))),
+            }
+        }
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.at;
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.at += 1;
+        }
+        self.at - start
+    }
+
+    fn number(&mut self) -> Result<Value, JsonError> {
+        let start = self.at;
+        let invalid = |parser: &Self| Err(parser.error(ErrorKind::InvalidNumber));
+
+        if self.peek() == Some(b'-') {
+            self.at += 1;
+        }
+        match self.peek() {
+            Some(b'0') => self.at += 1,
+            Some(b'1'..=b'9') => _ = self.digits(),
+            _ => return invalid(self),
+        }
+        let integer = self.at;
+        if self.peek() == Some(b'.') {
+            self.at += 1;
+            if self.digits() == 0 {
+                return invalid(self);
+            }
+        }
+        if let Some(b'e' | b'E') = self.peek() {
+            self.at += 1;
+            if let Some(b'+' | b'-') = self.peek() {
+                self.at += 1;
+            }
+            if self.digits() == 0 {
+                return invalid(self);
+            }
+        }
+
+        let text = &self.input[start..self.at];
+        if self.at == integer {
+            if let Ok(n) = text.parse() {
+                return Ok(Value::Number(Number::Integer(n)));
+            }
+        }
+        match text.parse() {
+            Ok(x) => Ok(Value::Number(Number::Float(x))),
+            Err(_) => {
+                self.at = start;
+                invalid(self)
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, JsonError> {
+        let hex = self.input.get(self.at..self.at + 4);
+        match hex.filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit())) {
+            Some(hex) => {
+                self.at += 4;
+                Ok(u32::from_str_radix(hex, 16).unwrap())
+            }
+            None => Err(self.error(ErrorKind::InvalidEscape)),
+        }
+    }
+
+    /// The code point of a `\u` escape, reading the second half of a surrogate pair
+    fn unicode_escape(&mut self) -> Result<char, JsonError> {
+        let start = self.at - 2;
+        let high = self.hex4()?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if !self.input[self.at..].starts_with("\\u") {
+                self.at = start;
+                return Err(self.error(ErrorKind::InvalidEscape));
+            }
+            self.at += 2;
+            let low = self.hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                self.at = start;
+                return Err(self.error(ErrorKind::InvalidEscape));
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+
+        char::from_u32(code).ok_or_else(|| {
+            self.at = start;
+            self.error(ErrorKind::InvalidEscape)
+        })
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        self.at += 1;
+        let mut string = String::new();
+        loop {
+            // Everything up to the next quote, backslash or control character is copied as is
+            let rest = &self.input[self.at..];
+            let plain = rest
+                .find(|c: char| c == '"' || c == '\\' || c < ' ')
+                .unwrap_or(rest.len());
+            string += &rest[..plain];
+            self.at += plain;
+
+            match self.peek() {
+                Some(b'"') => {
+                    self.at += 1;
+                    return Ok(string);
+                }
+                Some(b'\\') => {
+                    self.at += 1;
+                    let escaped = match self.peek() {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => {
+                            self.at += 1;
+                            string.push(self.unicode_escape()?);
+                            continue;
+                        }
+                        _ => {
+                            self.at -= 1;
+                            return Err(self.error(ErrorKind::InvalidEscape));
+                        }
+                    };
+                    self.at += 1;
+                    string.push(escaped);
+                }
+                Some(_) => return Err(self.error(ErrorKind::ControlCharacter)),
+                None => return Err(self.error(ErrorKind::Expected("'\"'"))),
+            }
+        }
+    }
+}
+
+pub fn parse(input: &str) -> Result<Value, JsonError> {
+    let mut parser = Parser {
+        input,
+        at: 0,
+        depth: 0,
+    };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.at < input.len() {
+        return Err(parser.error(ErrorKind::TrailingCharacters));
+    }
+    Ok(value)
+}
+
+impl FromStr for Value {
+    type Err = JsonError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Where a value is in a document, written JSONPath style: `$.a[1]["b c"]`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Path(pub Vec<Segment>);
+
+impl Display for Path {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "$")?;
+        for segment in &self.0 {
+            match segment {
+                Segment::Index(index) => write!(f, "[{index}]")?,
+                Segment::Key(key)
+                    if key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') =>
+                {
+                    write!(f, ".{key}")?
+                }
+                Segment::Key(key) => write!(f, "[{key:?}]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Called by `walk` on the way through a document. Every method has a default that does
+/// nothing, and objects are all entered unless `enter_object` says otherwise.
+pub trait Visitor {
+    /// Whether to go into this object: nothing in a skipped one is visited
+    fn enter_object(&mut self, _path: &Path, _members: &[(String, Value)]) -> bool {
+        true
+    }
+
+    fn number(&mut self, _path: &Path, _number: Number) {}
+
+    fn string(&mut self, _path: &Path, _string: &str) {}
+}
+
+/// Goes through `value` depth first, in document order
+pub fn walk(value: &Value, visitor: &mut impl Visitor) {
+    fn go(value: &Value, path: &mut Path, visitor: &mut impl Visitor) {
+        match value {
+            Value::Number(number) => visitor.number(path, *number),
+            Value::String(string) => visitor.string(path, string),
+            Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    path.0.push(Segment::Index(index));
+                    go(item, path, visitor);
+                    path.0.pop();
+                }
+            }
+            Value::Object(members) => {
+                if visitor.enter_object(path, members) {
+                    for (key, member) in members {
+                        path.0.push(Segment::Key(key.clone()));
+                        go(member, path, visitor);
+                        path.0.pop();
+                    }
+                }
+            }
+            Value::Null | Value::Bool(_) => {}
+        }
+    }
+
+    go(value, &mut Path::default(), visitor);
+}
+
+/// Collects the numbers of a document with their paths, leaving out those in objects with a
+/// member equal to the value given to `skip_objects_with`
+#[derive(Debug, Clone, Default)]
+pub struct Numbers {
+    skip: Option<Value>,
+    pub found: Vec<(Path, Number)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SumError {
+    /// A number that is not an integer, or one too large for an `i64`
+    NotInteger(Path),
+    Overflow,
+}
+
+impl Display for SumError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SumError::NotInteger(path) => write!(f, "{path} is not an integer fitting i64"),
+            SumError::Overflow => write!(f, "the sum does not fit i64"),
+        }
+    }
+}
+
+impl Error for SumError {}
+
+impl Numbers {
+    pub fn skip_objects_with(mut self, value: Value) -> Self {
+        self.skip = Some(value);
+        self
+    }
+
+    /// The exact sum of numbers that are all integers
+    pub fn integer_sum(&self) -> Result<i64, SumError> {
+        self.found
+            .iter()
+            .try_fold(0i64, |sum, (path, number)| match number {
+                Number::Integer(n) => sum.checked_add(*n).ok_or(SumError::Overflow),
+                Number::Float(_) => Err(SumError::NotInteger(path.clone())),
+            })
+    }
+
+    /// The floating point sum of any numbers
+    pub fn sum(&self) -> f64 {
+        self.found.iter().map(|(_, number)| number.as_f64()).sum()
+    }
+}
+
+impl Visitor for Numbers {
+    fn enter_object(&mut self, _path: &Path, members: &[(String, Value)]) -> bool {
+        !members
+            .iter()
+            .any(|(_, member)| Some(member) == self.skip.as_ref())
+    }
+
+    fn number(&mut self, path: &Path, number: Number) {
+        self.found.push((path.clone(), number));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let value = parse(" {\"a\": [1, -2.5e1, true, null], \"b\": {}, \"a\": \"x\"} ").unwrap();
+        assert_eq!(
+            value,
+            Value::Object(vec![
+                (
+                    "a".to_string(),
+                    Value::Array(vec![
+                        Value::Number(Number::Integer(1)),
+                        Value::Number(Number::Float(-25.0)),
+                        Value::Bool(true),
+                        Value::Null
+                    ])
+                ),
+                ("b".to_string(), Value::Object(vec![])),
+                ("a".to_string(), Value::String("x".to_string())),
+            ])
+        );
+        assert_eq!(
+            "\"{\\\"}\\\\\\n\\u00e9\\ud83c\\udf84ü\"".parse(),
+            Ok(Value::String("{\"}\\\né🎄ü".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |input: &str| {
+            let error = parse(input).unwrap_err();
+            (error.kind, error.at.line, error.at.column)
+        };
+        assert_eq!(error(""), (ErrorKind::Expected("a value"), 1, 1));
+        assert_eq!(
+            error("{\"a\":2,\"b\":4"),
+            (ErrorKind::Expected("',' or '}'"), 1, 13)
+        );
+        assert_eq!(
+            error("[1,\n  2 3]"),
+            (ErrorKind::Expected("',' or ']'"), 2, 5)
+        );
+        assert_eq!(error("{1: 2}"), (ErrorKind::Expected("a string key"), 1, 2));
+        assert_eq!(error("[01]"), (ErrorKind::Expected("',' or ']'"), 1, 3));
+        assert_eq!(error("[1.]"), (ErrorKind::InvalidNumber, 1, 4));
+        assert_eq!(error("[-]"), (ErrorKind::InvalidNumber, 1, 3));
+        assert_eq!(error("\"é\\x\""), (ErrorKind::InvalidEscape, 1, 3));
+        assert_eq!(error("\"\\ud83c\""), (ErrorKind::InvalidEscape, 1, 2));
+        assert_eq!(error("\"a\nb\""), (ErrorKind::ControlCharacter, 1, 3));
+        assert_eq!(error("\"abc"), (ErrorKind::Expected("'\"'"), 1, 5));
+        assert_eq!(error("[tru]"), (ErrorKind::Expected("a value"), 1, 2));
+        assert_eq!(error("{} {}"), (ErrorKind::TrailingCharacters, 1, 4));
+        assert_eq!(
+            error(&"[".repeat(MAX_DEPTH + 1)),
+            (ErrorKind::TooDeep, 1, MAX_DEPTH + 1)
+        );
+        let error = parse("[1,\n  2 3]").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 5: expected ',' or ']', found '3'"
+        );
+        assert_eq!(
+            error.at.snippet(),
+            "2 |   2 3]\n  |     ^ expected ',' or ']'"
+        );
+        assert_eq!(ParseError::from(error.clone()), error.at);
+    }
+
+    #[test]
+    fn test_numbers() {
+        let value = parse(r#"{"a": [1, {"b c": 2, "d": "red"}, {"e": [3]}], "f": 4}"#).unwrap();
+
+        let mut numbers = Numbers::default().skip_objects_with(Value::String("red".to_string()));
+        walk(&value, &mut numbers);
+        assert_eq!(numbers.integer_sum(), Ok(8));
+        let paths: Vec<String> = numbers
+            .found
+            .iter()
+            .map(|(path, _)| path.to_string())
+            .collect();
+        assert_eq!(paths, ["$.a[0]", "$.a[2].e[0]", "$.f"]);
+
+        let mut numbers = Numbers::default();
+        walk(&value, &mut numbers);
+        assert_eq!(numbers.found[1].0.to_string(), "$.a[1][\"b c\"]");
+        assert_eq!(numbers.integer_sum(), Ok(10));
+
+        // Integers stay exact past 2^53, where floating point would round them
+        let mut numbers = Numbers::default();
+        walk(&parse("[9007199254740993, 1, -0]").unwrap(), &mut numbers);
+        assert_eq!(numbers.integer_sum(), Ok(9007199254740994));
+
+        let value = parse(r#"{"a": [1, 0.5], "b": 1e3, "c": 99999999999999999999}"#).unwrap();
+        let mut numbers = Numbers::default();
+        walk(&value, &mut numbers);
+        assert_eq!(numbers.found[2].1, Number::Float(1000.0));
+        assert_eq!(numbers.found[3].1, Number::Float(1e20));
+        assert_eq!(numbers.sum(), 1e20 + 1001.5);
+        assert_eq!(
+            numbers.integer_sum(),
+            Err(SumError::NotInteger(Path(vec![
+                Segment::Key("a".to_string()),
+                Segment::Index(1)
+            ])))
+        );
+
+        let mut numbers = Numbers::default();
+        walk(&parse("[9223372036854775807, 1]").unwrap(), &mut numbers);
+        assert_eq!(numbers.integer_sum(), Err(SumError::Overflow));
+    }
+}
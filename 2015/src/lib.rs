@@ -1,25 +1,85 @@
-mod day19;
-mod day18;
-mod day17;
-mod day16;
-mod day15;
-mod day14;
+pub mod day19;
+pub mod day18;
+pub mod day17;
+pub mod day16;
+pub mod day15;
+pub mod day14;
 mod day13;
-mod day12;
-mod day11;
-mod day10;
+pub mod day12;
+pub mod day11;
+pub mod day10;
 mod day9;
-mod day8;
-mod day7;
-mod day6;
+pub mod day8;
+pub mod day7;
+pub mod day6;
 mod day5;
-mod day4;
+pub mod day4;
 mod day3;
 mod day2;
 mod day1;
+pub mod json;
+pub mod tsp;
 extern crate aoc_runner;
 
 #[macro_use]
 extern crate aoc_runner_derive;
 
+use advent_of_code_common::solver::Solver;
+
+pub const SOLVERS: &[Solver] = &[
+    Solver::new(1, 1, Factory::day1_part1),
+    Solver::new(1, 2, Factory::day1_part2),
+    Solver::new(2, 1, Factory::day2_part1),
+    Solver::new(2, 2, Factory::day2_part2),
+    Solver::new(3, 1, Factory::day3_part1),
+    Solver::new(3, 2, Factory::day3_part2),
+    Solver::new(4, 1, Factory::day4_part1),
+    Solver::new(4, 2, Factory::day4_part2),
+    Solver::new(5, 1, Factory::day5_part1),
+    Solver::new(5, 2, Factory::day5_part2),
+    Solver::new(6, 1, Factory::day6_part1),
+    Solver::new(6, 2, Factory::day6_part2),
+    Solver::new(7, 1, Factory::day7_part1),
+    Solver::new(7, 2, Factory::day7_part2),
+    Solver::new(8, 1, Factory::day8_part1),
+    Solver::new(8, 2, Factory::day8_part2),
+    Solver::new(9, 1, Factory::day9_part1),
+    Solver::new(9, 2, Factory::day9_part2),
+    Solver::new(10, 1, Factory::day10_part1),
+    Solver::new(10, 2, Factory::day10_part2),
+    Solver::new(11, 1, Factory::day11_part1),
+    Solver::new(11, 2, Factory::day11_part2),
+    Solver::new(12, 1, Factory::day12_part1),
+    Solver::new(12, 2, Factory::day12_part2),
+    Solver::new(13, 1, Factory::day13_part1),
+    Solver::new(13, 2, Factory::day13_part2),
+    Solver::new(14, 1, Factory::day14_part1),
+    Solver::new(14, 2, Factory::day14_part2),
+    Solver::new(15, 1, Factory::day15_part1),
+    Solver::new(15, 2, Factory::day15_part2),
+    Solver::new(16, 1, Factory::day16_part1),
+    Solver::new(16, 2, Factory::day16_part2),
+    Solver::new(17, 1, Factory::day17_part1),
+    Solver::new(17, 2, Factory::day17_part2),
+    Solver::new(18, 1, Factory::day18_part1),
+    Solver::new(18, 2, Factory::day18_part2),
+    Solver::new(19, 1, Factory::day19_part1),
+    Solver::new(19, 2, Factory::day19_part2),
+];
+
 aoc_lib! { year = 2015 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use advent_of_code_common::solver;
+
+    use super::*;
+
+    #[test]
+    fn test_every_solver_listed() {
+        let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        assert_eq!(solver::unlisted(SOLVERS, &src).unwrap(), []);
+    }
+}
@@ -0,0 +1,330 @@
+//! Best routes through every node of a small weighted graph, by Held–Karp: the best way to visit
+//! each subset of nodes ending at each node, built up one node at a time. `O(2^n · n²)` instead
+//! of trying all `n!` orders.
+
+use std::{collections::HashMap, fmt::Display};
+
+/// Held–Karp keeps `2^n · n` partial routes, a million at this size and doubling with each node
+pub const MAX_NODES: usize = 16;
+
+/// Directed weighted edges between named nodes, interned as they are first seen
+#[derive(Debug, Clone, Default)]
+pub struct Graph {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+    weights: HashMap<(usize, usize), i64>,
+}
+
+impl Graph {
+    pub fn node(&mut self, name: &str) -> usize {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), self.names.len() - 1);
+        self.names.len() - 1
+    }
+
+    /// Sets the weight of going from `from` to `to`, leaving the other direction alone
+    pub fn set(&mut self, from: &str, to: &str, weight: i64) {
+        let (from, to) = (self.node(from), self.node(to));
+        self.weights.insert((from, to), weight);
+    }
+
+    pub fn weight(&self, from: &str, to: &str) -> Option<i64> {
+        let (from, to) = (self.ids.get(from)?, self.ids.get(to)?);
+        self.weights.get(&(*from, *to)).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(String::as_str)
+    }
+
+    /// The weights as a matrix, or the first pair of distinct nodes without an edge
+    fn matrix(&self) -> Result<Vec<Vec<i64>>, RouteError> {
+        let n = self.len();
+        let weight = |from: usize, to: usize| match self.weights.get(&(from, to)) {
+            _ if from == to => Ok(0),
+            Some(&weight) => Ok(weight),
+            None => Err(RouteError::MissingEdge {
+                from: self.names[from].clone(),
+                to: self.names[to].clone(),
+            }),
+        };
+
+        (0..n)
+            .map(|from| (0..n).map(|to| weight(from, to)).collect())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+    /// Starts and ends anywhere
+    Path,
+    /// Comes back to where it started
+    Cycle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    Min,
+    Max,
+}
+
+impl Goal {
+    fn better(self, a: i64, b: i64) -> bool {
+        match self {
+            Goal::Min => a < b,
+            Goal::Max => a > b,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouteError {
+    /// There is no edge from `from` to `to`
+    MissingEdge {
+        from: String,
+        to: String,
+    },
+    TooManyNodes(usize),
+}
+
+impl Display for RouteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RouteError::MissingEdge { from, to } => {
+                write!(f, "no edge from {from} to {to}")
+            }
+            RouteError::TooManyNodes(n) => {
+                write!(
+                    f,
+                    "{n} nodes are too many, at most {MAX_NODES} are supported"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for RouteError {}
+
+/// The nodes in the order they are visited, a cycle's first node not being repeated at the end
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route<'a> {
+    pub cost: i64,
+    pub order: Vec<&'a str>,
+}
+
+impl Display for Route<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.order.join(" -> "), self.cost)
+    }
+}
+
+/// The best route through every node of `graph`. Cycles all start from the first node seen,
+/// paths from whichever node is best.
+pub fn solve(graph: &Graph, shape: Shape, goal: Goal) -> Result<Route<'_>, RouteError> {
+    let n = graph.len();
+    if n > MAX_NODES {
+        return Err(RouteError::TooManyNodes(n));
+    }
+    let weight = graph.matrix()?;
+    if n == 0 {
+        return Ok(Route {
+            cost: 0,
+            order: vec![],
+        });
+    }
+
+    // best[mask][last]: the cost of the best route through `mask` ending at `last`,
+    // and the node before `last` on it
+    let full = (1 << n) - 1;
+    let mut best: Vec<Vec<Option<(i64, usize)>>> = vec![vec![None; n]; 1 << n];
+    match shape {
+        Shape::Path => (0..n).for_each(|start| best[1 << start][start] = Some((0, start))),
+        Shape::Cycle => best[1][0] = Some((0, 0)),
+    }
+
+    for mask in 1..=full {
+        for last in (0..n).filter(|last| mask & 1 << last != 0) {
+            let Some((cost, _)) = best[mask][last] else {
+                continue;
+            };
+            for next in (0..n).filter(|next| mask & 1 << next == 0) {
+                let cost = cost + weight[last][next];
+                let entry = &mut best[mask | 1 << next][next];
+                if entry.is_none_or(|(old, _)| goal.better(cost, old)) {
+                    *entry = Some((cost, last));
+                }
+            }
+        }
+    }
+
+    let closing = |last: usize| match shape {
+        Shape::Path => 0,
+        Shape::Cycle => weight[last][0],
+    };
+    let (cost, mut last) = (0..n)
+        .filter_map(|last| best[full][last].map(|(cost, _)| (cost + closing(last), last)))
+        .reduce(|a, b| if goal.better(b.0, a.0) { b } else { a })
+        .expect("a complete graph has a route through every node");
+
+    let mut order = vec![];
+    let mut mask = full;
+    while mask != 0 {
+        order.push(graph.names[last].as_str());
+        let (_, previous) = best[mask][last].unwrap();
+        mask &= !(1 << last);
+        last = previous;
+    }
+    order.reverse();
+
+    Ok(Route { cost, order })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(&str, &str, i64)]) -> Graph {
+        let mut graph = Graph::default();
+        for &(from, to, weight) in edges {
+            graph.set(from, to, weight);
+        }
+        graph
+    }
+
+    /// The best cost over every order, the way the days used to search
+    fn brute_force(graph: &Graph, shape: Shape, goal: Goal) -> i64 {
+        fn go(
+            graph: &Graph,
+            weight: &[Vec<i64>],
+            order: &mut Vec<usize>,
+            shape: Shape,
+            goal: Goal,
+        ) -> Option<i64> {
+            let n = graph.len();
+            if order.len() == n {
+                let cost = order.windows(2).map(|w| weight[w[0]][w[1]]).sum::<i64>();
+                return Some(match shape {
+                    Shape::Path => cost,
+                    Shape::Cycle => cost + weight[order[n - 1]][order[0]],
+                });
+            }
+            let unvisited: Vec<usize> = (0..n).filter(|next| !order.contains(next)).collect();
+            unvisited
+                .into_iter()
+                .filter_map(|next| {
+                    order.push(next);
+                    let cost = go(graph, weight, order, shape, goal);
+                    order.pop();
+                    cost
+                })
+                .reduce(|a, b| if goal.better(b, a) { b } else { a })
+        }
+
+        go(graph, &graph.matrix().unwrap(), &mut vec![], shape, goal).unwrap()
+    }
+
+    #[test]
+    fn test_asymmetric() {
+        let names = ["a", "b", "c", "d", "e", "f"];
+        let mut seed = 11i64;
+        let mut edges = vec![];
+        for from in names {
+            for to in names.iter().filter(|&&to| to != from) {
+                seed = (seed * 1_103_515_245 + 12345) % (1 << 31);
+                edges.push((from, *to, seed % 100 - 30));
+            }
+        }
+        let graph = graph(&edges);
+
+        for shape in [Shape::Path, Shape::Cycle] {
+            for goal in [Goal::Min, Goal::Max] {
+                let route = solve(&graph, shape, goal).unwrap();
+                assert_eq!(
+                    route.cost,
+                    brute_force(&graph, shape, goal),
+                    "{shape:?} {goal:?}"
+                );
+
+                let mut visited = route.order.clone();
+                visited.sort_unstable();
+                assert_eq!(visited, names);
+
+                let id = |name: &str| graph.ids[name];
+                let mut cost: i64 = route
+                    .order
+                    .windows(2)
+                    .map(|w| graph.weights[&(id(w[0]), id(w[1]))])
+                    .sum();
+                if shape == Shape::Cycle {
+                    cost += graph.weights[&(id(route.order[5]), id(route.order[0]))];
+                }
+                assert_eq!(cost, route.cost);
+            }
+        }
+    }
+
+    #[test]
+    fn test_route() {
+        let graph = graph(&[
+            ("London", "Dublin", 464),
+            ("Dublin", "London", 464),
+            ("London", "Belfast", 518),
+            ("Belfast", "London", 518),
+            ("Dublin", "Belfast", 141),
+            ("Belfast", "Dublin", 141),
+        ]);
+        let route = solve(&graph, Shape::Path, Goal::Min).unwrap();
+        assert_eq!(route.cost, 605);
+        assert!(
+            route.order == ["London", "Dublin", "Belfast"]
+                || route.order == ["Belfast", "Dublin", "London"]
+        );
+        assert_eq!(
+            solve(&Graph::default(), Shape::Cycle, Goal::Max)
+                .unwrap()
+                .cost,
+            0
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let graph = graph(&[
+            ("a", "b", 1),
+            ("b", "a", 1),
+            ("b", "c", 1),
+            ("c", "b", 1),
+            ("c", "a", 1),
+        ]);
+        let error = solve(&graph, Shape::Path, Goal::Min).unwrap_err();
+        assert_eq!(
+            error,
+            RouteError::MissingEdge {
+                from: "a".to_string(),
+                to: "c".to_string()
+            }
+        );
+        assert_eq!(error.to_string(), "no edge from a to c");
+
+        let mut large = Graph::default();
+        for n in 0..=MAX_NODES {
+            large.node(&n.to_string());
+        }
+        assert_eq!(
+            solve(&large, Shape::Cycle, Goal::Min),
+            Err(RouteError::TooManyNodes(MAX_NODES + 1))
+        );
+    }
+}
@@ -1,21 +1,22 @@
 use std::collections::BTreeMap;
 
+use advent_of_code_common::parse::{lines, ParseError};
+
 #[aoc_generator(day1)]
-fn parse(input: &str) -> (Vec<i32>, Vec<i32>) {
+fn parse(input: &str) -> Result<(Vec<i32>, Vec<i32>), ParseError> {
     let mut v1 = vec![];
     let mut v2 = vec![];
 
-    input
-        .lines()
-        .map(|line| line.split_whitespace().map(|x| x.parse::<i32>().unwrap()))
-        .for_each(|mut item| {
-            v1.push(item.next().unwrap());
-            v2.push(item.next().unwrap())
-        });
+    for mut line in lines(input) {
+        v1.push(line.number()?);
+        line.skip_whitespace();
+        v2.push(line.number()?);
+        line.end()?;
+    }
 
     v1.sort();
     v2.sort();
-    (v1, v2)
+    Ok((v1, v2))
 }
 
 #[aoc(day01, part1)]
@@ -67,12 +68,19 @@ mod tests {
     use super::*;
     #[test]
     fn test_base() {
-        assert_eq!(part1(&parse(EXAMPLE)), 11);
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 11);
     }
 
     use super::part2;
     #[test]
     fn test_base_similarity() {
-        assert_eq!(part2(&parse(EXAMPLE)), 31);
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 31);
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("3   4\n4   ").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 5));
+        assert_eq!(error.expected, "number");
     }
 }
@@ -1,34 +1,23 @@
+use advent_of_code_common::parse::{check_map, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
+use grid::{Grid, Position};
 use std::collections::BTreeMap;
 
 #[aoc_generator(day10)]
-fn parse(input: &str) -> (Vec<Vec<i32>>, Vec<(usize, usize)>) {
-    let mut board = vec![];
-    let mut starting_points = vec![];
-    let border = std::iter::repeat(-1);
-    board.push(Vec::new());
+fn parse(input: &str) -> Result<(Grid<u8>, Vec<Position>), ParseError> {
+    check_map(input, |c| c.is_ascii_digit(), "digit")?;
+    let board = Grid::parse(input, |digit| digit as u8 - b'0');
+    let starting_points = board
+        .iter()
+        .filter(|(_, &height)| height == 0)
+        .map(|(position, _)| position)
+        .collect();
 
-    for (y, line) in input.lines().enumerate() {
-        let mut new_line = vec![];
-        new_line.push(-1);
-        for (x, digit) in line.chars().enumerate() {
-            let digit = digit.to_digit(10).unwrap() as i32;
-            new_line.push(digit);
-            if digit == 0 {
-                starting_points.push((x + 1, y + 1))
-            }
-        }
-        new_line.push(-1);
-        board.push(new_line);
-    }
-    board[0] = border.clone().take(board[1].len()).collect();
-    board.push(border.take(board[1].len()).collect());
-
-    (board, starting_points)
+    Ok((board, starting_points))
 }
 
 #[aoc(day10, part1)]
-fn part1((board, starting_points): &(Vec<Vec<i32>>, Vec<(usize, usize)>)) -> usize {
+fn part1((board, starting_points): &(Grid<u8>, Vec<Position>)) -> usize {
     starting_points.iter().fold(0, |acc, &point| {
         let results = &mut BTreeMap::new();
         search_depth(board, point, results);
@@ -37,7 +26,7 @@ fn part1((board, starting_points): &(Vec<Vec<i32>>, Vec<(usize, usize)>)) -> usi
 }
 
 #[aoc(day10, part2)]
-fn part2((board, starting_points): &(Vec<Vec<i32>>, Vec<(usize, usize)>)) -> usize {
+fn part2((board, starting_points): &(Grid<u8>, Vec<Position>)) -> usize {
     starting_points.iter().fold(0, |acc, &point| {
         let results = &mut BTreeMap::new();
         search_depth(board, point, results);
@@ -45,28 +34,16 @@ fn part2((board, starting_points): &(Vec<Vec<i32>>, Vec<(usize, usize)>)) -> usi
     })
 }
 
-fn search_depth(
-    board: &Vec<Vec<i32>>,
-    (x, y): (usize, usize),
-    results: &mut BTreeMap<(usize, usize), u32>,
-) {
-    if board[y][x] == 9 {
+fn search_depth(board: &Grid<u8>, position: Position, results: &mut BTreeMap<Position, u32>) {
+    if board[position] == 9 {
         results
-            .entry((x, y))
+            .entry(position)
             .and_modify(|count| *count += 1)
             .or_insert(1);
     } else {
-        for dy in [-1, 1] {
-            let ny = (y as i32 + dy) as usize;
-            if board[ny][x] == board[y][x] + 1 {
-                search_depth(board, (x, ny), results)
-            }
-        }
-
-        for dx in [-1, 1] {
-            let nx = (x as i32 + dx) as usize;
-            if board[y][nx] == board[y][x] + 1 {
-                search_depth(board, (nx, y), results)
+        for next in board.neighbours4(position) {
+            if board[next] == board[position] + 1 {
+                search_depth(board, next, results)
             }
         }
     }
@@ -87,7 +64,7 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse(EXAMPLE)), 36);
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 36);
     }
 
     const EXAMPLE2: &str = "012345
@@ -98,7 +75,14 @@ mod tests {
 567891";
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse(EXAMPLE)), 81);
-        assert_eq!(part2(&parse(EXAMPLE2)), 227);
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 81);
+        assert_eq!(part2(&parse(EXAMPLE2).unwrap()), 227);
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("0123\n1.34").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+        assert_eq!(error.expected, "digit");
     }
 }
@@ -1,12 +1,17 @@
+use advent_of_code_common::parse::{lines, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 use std::collections::HashMap;
 
 #[aoc_generator(day11)]
-fn parse(input: &str) -> Vec<u64> {
-    input
-        .split_whitespace()
-        .map(|s| s.parse::<u64>().unwrap())
-        .collect::<Vec<u64>>()
+fn parse(input: &str) -> Result<Vec<u64>, ParseError> {
+    let mut stones = vec![];
+    for mut line in lines(input) {
+        while !line.is_empty() {
+            stones.push(line.number()?);
+            line.skip_whitespace();
+        }
+    }
+    Ok(stones)
 }
 
 #[aoc(day11, part1)]
@@ -30,7 +35,7 @@ fn blink(n: u16, stone: u64, cache: &mut HashMap<(u64, u16), u64>) -> u64 {
 
     let next = if stone == 0 {
         blink(n - 1, 1, cache)
-    } else if stone.to_string().len() % 2 == 0 {
+    } else if stone.to_string().len().is_multiple_of(2) {
         let stone_str = stone.to_string();
         let half = stone_str.len() / 2;
         let left = stone_str[..half].parse::<u64>().unwrap();
@@ -58,7 +63,14 @@ mod tests {
 
     #[test]
     fn part1_2_example() {
-        assert_eq!(blink_n(6, &parse("125 17")), 22);
-        assert_eq!(blink_n(25, &parse("125 17")), 55312);
+        assert_eq!(blink_n(6, &parse("125 17").unwrap()), 22);
+        assert_eq!(blink_n(25, &parse("125 17").unwrap()), 55312);
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("125 17 -").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 8));
+        assert_eq!(error.expected, "number");
     }
 }
@@ -1,9 +1,8 @@
+use advent_of_code_common::parse::{check_map, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
-use itertools::Itertools;
+use grid::Grid;
 use std::collections::BTreeSet;
 
-const BORDER: char = '#';
-
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
 struct Position(i16, i16);
 
@@ -138,54 +137,35 @@ impl Region {
 }
 
 #[aoc_generator(day12)]
-fn parse(input: &str) -> Vec<Region> {
-    let mut board = vec![vec![]];
-    board.append(
-        &mut input
-            .lines()
-            .map(|line| {
-                let mut l = vec![BORDER];
-                l.append(&mut line.chars().collect_vec());
-                l.push(BORDER);
-                l
-            })
-            .collect_vec(),
-    );
-
-    let size = board[1].len();
-    let border = std::iter::repeat(BORDER);
-    board[0] = border.clone().take(size).collect();
-    board.push(border.take(size).collect());
+fn parse(input: &str) -> Result<Vec<Region>, ParseError> {
+    check_map(input, |c| c.is_ascii_uppercase(), "plant (A-Z)")?;
+    let board = Grid::parse(input, |c| c);
 
     let mut regions = Vec::new();
     let mut to_visit = BTreeSet::new();
     let mut visited = BTreeSet::new();
-    to_visit.insert((1, 1));
+    to_visit.insert((0, 0));
 
-    while let Some((x, y)) = to_visit.iter().next().cloned() {
-        to_visit.remove(&(x, y));
-        if visited.contains(&(x, y)) {
+    while let Some(position) = to_visit.pop_first() {
+        if visited.contains(&position) {
             continue;
         }
 
-        let char = board[y][x];
-        let mut region = Region::new((x, y).into());
+        let char = board[position];
+        let mut region = Region::new(position.into());
         let mut same_region = Vec::new();
-        same_region.push((x, y));
-
-        while let Some((x, y)) = same_region.pop() {
-            if !visited.contains(&(x, y)) {
-                visited.insert((x, y));
-
-                for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
-                    let nx = x.saturating_add_signed(dx as isize);
-                    let ny = y.saturating_add_signed(dy as isize);
-
-                    if board[ny][nx] == char {
-                        region.insert((nx, ny).into());
-                        same_region.push((nx, ny));
-                    } else if board[ny][nx] != BORDER {
-                        to_visit.insert((nx, ny));
+        same_region.push(position);
+
+        while let Some(position) = same_region.pop() {
+            if !visited.contains(&position) {
+                visited.insert(position);
+
+                for next in board.neighbours4(position) {
+                    if board[next] == char {
+                        region.insert(next.into());
+                        same_region.push(next);
+                    } else {
+                        to_visit.insert(next);
                     }
                 }
             }
@@ -193,7 +173,7 @@ fn parse(input: &str) -> Vec<Region> {
         regions.push(region);
     }
 
-    regions
+    Ok(regions)
 }
 
 #[aoc(day12, part1)]
@@ -254,15 +234,22 @@ AAAAAA";
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse(EXAMPLE)), 140);
-        assert_eq!(part1(&parse(EXAMPLE2)), 772);
-        assert_eq!(part1(&parse(EXAMPLE3)), 1930);
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 140);
+        assert_eq!(part1(&parse(EXAMPLE2).unwrap()), 772);
+        assert_eq!(part1(&parse(EXAMPLE3).unwrap()), 1930);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse(EXAMPLE)), 80);
-        assert_eq!(part2(&parse(EXAMPLE4)), 236);
-        assert_eq!(part2(&parse(EXAMPLE5)), 368);
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 80);
+        assert_eq!(part2(&parse(EXAMPLE4).unwrap()), 236);
+        assert_eq!(part2(&parse(EXAMPLE5).unwrap()), 368);
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("AAAA\nBBCD\nBBC").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 4));
+        assert_eq!(error.expected, "plant (A-Z)");
     }
 }
@@ -1,3 +1,4 @@
+use advent_of_code_common::parse::{lines, Cursor, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 use std::str::FromStr;
 
@@ -11,43 +12,37 @@ struct Machine {
     y_prize: i64,
 }
 
-#[derive(Debug)]
-struct ParseMachineError;
-
 impl FromStr for Machine {
-    type Err = ParseMachineError;
+    type Err = ParseError;
 
     //Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=8400, Y=5400\n
-    fn from_str(input: &str) -> Result<Machine, ParseMachineError> {
-        let mut parts = input.lines();
-        let (x_a, y_a) = parts
-            .next()
-            .unwrap()
-            .strip_prefix("Button A: X+")
-            .unwrap()
-            .split_once(", Y+")
-            .unwrap();
-        let (x_b, y_b) = parts
-            .next()
-            .unwrap()
-            .strip_prefix("Button B: X+")
-            .unwrap()
-            .split_once(", Y+")
-            .unwrap();
-        let (x_prize, y_prize) = parts
-            .next()
-            .unwrap()
-            .strip_prefix("Prize: X=")
-            .unwrap()
-            .split_once(", Y=")
-            .unwrap();
+    fn from_str(input: &str) -> Result<Machine, ParseError> {
+        let mut lines = lines(input);
+        let mut pair = |number, prefix, separator| -> Result<(i64, i64), ParseError> {
+            let mut line = lines.next().unwrap_or(Cursor::new(number, ""));
+            line.literal(prefix)?;
+            let x = line.number()?;
+            line.literal(separator)?;
+            let y = line.number()?;
+            line.end()?;
+            Ok((x, y))
+        };
+
+        let (x_a, y_a) = pair(1, "Button A: X+", ", Y+")?;
+        let (x_b, y_b) = pair(2, "Button B: X+", ", Y+")?;
+        let (x_prize, y_prize) = pair(3, "Prize: X=", ", Y=")?;
+
+        if let Some(line) = lines.next() {
+            return Err(line.error("blank line"));
+        }
+
         Ok(Machine {
-            x_a: x_a.parse().unwrap(),
-            x_b: x_b.parse().unwrap(),
-            y_a: y_a.parse().unwrap(),
-            y_b: y_b.parse().unwrap(),
-            x_prize: x_prize.parse().unwrap(),
-            y_prize: y_prize.parse().unwrap(),
+            x_a,
+            x_b,
+            y_a,
+            y_b,
+            x_prize,
+            y_prize,
         })
     }
 }
@@ -75,11 +70,16 @@ impl Machine {
 }
 
 #[aoc_generator(day13)]
-fn parse(input: &str) -> Vec<Machine> {
+fn parse(input: &str) -> Result<Vec<Machine>, ParseError> {
+    let mut offset = 0;
     input
         .split("\n\n")
-        .map(|machine| machine.parse().unwrap())
-        .collect::<Vec<Machine>>()
+        .map(|machine| {
+            let start = offset;
+            offset += machine.lines().count() + 1;
+            machine.parse::<Machine>().map_err(|e| e.shifted(start))
+        })
+        .collect()
 }
 
 #[aoc(day13, part1)]
@@ -120,6 +120,20 @@ Prize: X=18641, Y=10279";
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse(EXAMPLE)), 480);
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 480);
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = parse(&EXAMPLE[..EXAMPLE.len() - 40]).unwrap_err();
+        assert_eq!((error.line, error.column), (14, 1));
+        assert_eq!(error.expected, "'Button B: X+'");
+
+        let error = parse("Button A: X+94, Y+34\nButton B: X+22, Y+67").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 1));
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 1: expected 'Prize: X=', found end of line"
+        );
     }
 }
@@ -4,6 +4,7 @@ use std::{
     ops::{AddAssign, Range},
 };
 
+use advent_of_code_common::parse::{lines, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
 
@@ -127,21 +128,27 @@ impl Space {
 }
 
 #[aoc_generator(day14)]
-fn parse(input: &str) -> Vec<Robot> {
+fn parse(input: &str) -> Result<Vec<Robot>, ParseError> {
     let mut robots: Vec<Robot> = vec![];
     // p=0,4 v=3,-3
-    input.lines().for_each(|line| {
-        let (position, velocity) = line.strip_prefix("p=").unwrap().split_once(" v=").unwrap();
-        let (position_x, position_y) = position.split_once(',').unwrap();
-        let (velocity_x, velocity_y) = velocity.split_once(',').unwrap();
+    for mut line in lines(input) {
+        line.literal("p=")?;
+        let position_x = line.number()?;
+        line.literal(",")?;
+        let position_y = line.number()?;
+        line.literal(" v=")?;
+        let velocity_x = line.number()?;
+        line.literal(",")?;
+        let velocity_y = line.number()?;
+        line.end()?;
 
         robots.push(Robot::new(
-            Position(position_x.parse().unwrap(), position_y.parse().unwrap()),
-            Velocity(velocity_x.parse().unwrap(), velocity_y.parse().unwrap()),
+            Position(position_x, position_y),
+            Velocity(velocity_x, velocity_y),
         ))
-    });
+    }
 
-    robots
+    Ok(robots)
 }
 
 fn simulation(
@@ -196,11 +203,18 @@ p=9,5 v=-3,-3";
 
     #[test]
     fn part1_example() {
-        assert_eq!(simulation(&parse(EXAMPLE), 11, 7, 100, false), 12);
+        assert_eq!(simulation(&parse(EXAMPLE).unwrap(), 11, 7, 100, false), 12);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(simulation(&parse(EXAMPLE), 11, 7, 100, true), 12);
+        assert_eq!(simulation(&parse(EXAMPLE).unwrap(), 11, 7, 100, true), 12);
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("p=0,4 v=3,-3\np=6,3 v=-1").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 11));
+        assert_eq!(error.expected, "','");
     }
 }
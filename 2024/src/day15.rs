@@ -1,8 +1,9 @@
 use core::fmt;
 use std::{fmt::Display, str::FromStr};
 
+use advent_of_code_common::parse::{check_map, lines, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
-use itertools::Itertools;
+use grid::Grid;
 
 enum Direction {
     Up,
@@ -25,7 +26,7 @@ impl Block {
 #[derive(Debug, Clone)]
 struct Map {
     robot: (u16, u16),
-    blocks: Vec<Vec<Option<Block>>>,
+    blocks: Grid<Option<Block>>,
 }
 
 impl Map {
@@ -37,14 +38,13 @@ impl Map {
             Direction::Left => (x - 1, y),
         };
 
-        if x >= self.blocks[0].len() as u16 || y >= self.blocks.len() as u16 {
+        let Some(&block) = self.blocks.get((nx as usize, ny as usize)) else {
             return false;
-        }
-        let block = self.blocks[ny as usize][nx as usize];
+        };
 
         if block.is_none() || (block.unwrap().is_movable() && self.displace((nx, ny), direction)) {
-            self.blocks[ny as usize][nx as usize] = self.blocks[y as usize][x as usize];
-            self.blocks[y as usize][x as usize] = None;
+            self.blocks[(nx as usize, ny as usize)] = self.blocks[(x as usize, y as usize)];
+            self.blocks[(x as usize, y as usize)] = None;
             return true;
         }
         false
@@ -68,55 +68,53 @@ impl Map {
     fn get_boxes(&self) -> Vec<(u16, u16)> {
         self.blocks
             .iter()
-            .enumerate()
-            .flat_map(|(y, line)| {
-                line.iter()
-                    .enumerate()
-                    .filter(|(_, block)| block.is_some() && block.unwrap().is_movable())
-                    .map(move |(x, _)| (x as u16, y as u16))
-            })
-            .collect_vec()
+            .filter(|(_, block)| block.is_some_and(|block| block.is_movable()))
+            .map(|((x, y), _)| (x as u16, y as u16))
+            .collect()
     }
 }
 
-struct MapError;
-
 impl FromStr for Map {
-    type Err = MapError;
+    type Err = ParseError;
 
     fn from_str(input: &str) -> std::result::Result<Self, <Self as std::str::FromStr>::Err> {
-        let mut blocks = vec![];
-        let mut robot = (0, 0);
-        for line_vec in input.lines().enumerate().map(|(y, line)| {
-            line.chars()
-                .enumerate()
-                .map(|(x, char)| (x, y, char))
-                .collect_vec()
-        }) {
-            let mut line = vec![];
-
-            for (x, y, char) in line_vec {
-                match char {
-                    '#' => line.push(Some(Block { movable: false })),
-                    '.' => line.push(None),
-                    'O' => line.push(Some(Block { movable: true })),
-                    '@' => {
-                        line.push(None);
-                        robot = (x as u16, y as u16)
-                    }
-                    _ => return Err(MapError),
-                }
-            }
-            blocks.push(line);
+        check_map(
+            input,
+            |char| "#.O@".contains(char),
+            "one of '#', '.', 'O', '@'",
+        )?;
+        let chars = Grid::parse(input, |char| char);
+
+        let mut robots = chars.iter().filter(|&(_, &char)| char == '@');
+        let Some(((x, y), _)) = robots.next() else {
+            let line = chars.height() + 1;
+            return Err(ParseError::new(line, 1, "a robot '@'", ""));
+        };
+        if let Some(((other_x, other_y), _)) = robots.next() {
+            let text = input.lines().nth(other_y).unwrap_or_default();
+            return Err(ParseError::new(
+                other_y + 1,
+                other_x + 1,
+                "a single robot '@'",
+                text,
+            ));
         }
+        let blocks = chars.map(|char| match char {
+            '#' => Some(Block { movable: false }),
+            'O' => Some(Block { movable: true }),
+            _ => None,
+        });
 
-        Ok(Map { robot, blocks })
+        Ok(Map {
+            robot: (x as u16, y as u16),
+            blocks,
+        })
     }
 }
 
 impl Display for Map {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for (y, row) in self.blocks.iter().enumerate() {
+        for (y, row) in self.blocks.rows().enumerate() {
             for (x, c) in row.iter().enumerate() {
                 if c.is_none() {
                     if (x as u16, y as u16) == self.get_robot() {
@@ -136,13 +134,21 @@ impl Display for Map {
     }
 }
 #[aoc_generator(day15)]
-fn parse(input: &str) -> (Map, String) {
-    let (map, path) = input.split_once("\n\n").unwrap();
-    let Ok(map) = map.parse() else {
-        panic!("Wrong map")
+fn parse(input: &str) -> Result<(Map, String), ParseError> {
+    let Some((map, path)) = input.split_once("\n\n") else {
+        let line = input.lines().count() + 1;
+        return Err(ParseError::new(line, 1, "blank line followed by moves", ""));
     };
 
-    (map, path.to_string())
+    let offset = map.lines().count() + 1;
+    for mut line in lines(path) {
+        line.take_while(|char| "<>^v".contains(char));
+        if !line.is_empty() {
+            return Err(line.error("one of '<', '>', '^', 'v'").shifted(offset));
+        }
+    }
+
+    Ok((map.parse()?, path.to_string()))
 }
 
 #[aoc(day15, part1)]
@@ -224,8 +230,29 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse(EXAMPLE1)), 10092);
-        assert_eq!(part1(&parse(EXAMPLE2)), 2028);
+        assert_eq!(part1(&parse(EXAMPLE1).unwrap()), 10092);
+        assert_eq!(part1(&parse(EXAMPLE2).unwrap()), 2028);
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("####\n#@O#\n####").unwrap_err();
+        assert_eq!((error.line, error.column), (4, 1));
+
+        let error = parse("####\n#@x#\n####\n\n<<").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+
+        let error = parse("####\n#@O#\n####\n\n<<\n>>A").unwrap_err();
+        assert_eq!((error.line, error.column), (6, 3));
+        assert_eq!(error.found(), "A");
+
+        let error = parse("####\n#.O#\n####\n\n<<").unwrap_err();
+        assert_eq!((error.line, error.column), (4, 1));
+        assert_eq!(error.expected, "a robot '@'");
+
+        let error = parse("####\n#@.#\n#.@#\n####\n\n<<").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 3));
+        assert_eq!(error.found(), "@");
     }
 
     // #[test]
@@ -1,3 +1,4 @@
+use advent_of_code_common::parse::{lines, ParseError};
 use itertools::Itertools;
 
 #[derive(PartialEq)]
@@ -20,29 +21,43 @@ impl Trend {
     }
 }
 
+#[aoc_generator(day02)]
+fn parse(input: &str) -> Result<Vec<Vec<i32>>, ParseError> {
+    let mut reports = vec![];
+
+    for mut line in lines(input) {
+        let mut report = vec![];
+        loop {
+            report.push(line.number()?);
+            line.skip_whitespace();
+            if line.is_empty() {
+                break;
+            }
+        }
+        reports.push(report);
+    }
+
+    Ok(reports)
+}
+
 #[aoc(day02, part1)]
-fn part1(input: &str) -> usize {
-    parse_apply(input, &validate)
+fn part1(reports: &[Vec<i32>]) -> usize {
+    count_valid(reports, &validate)
 }
 
 #[aoc(day02, part2)]
-fn part2(input: &str) -> usize {
-    parse_apply(input, &validate_with_skip)
+fn part2(reports: &[Vec<i32>]) -> usize {
+    count_valid(reports, &validate_with_skip)
 }
 
-fn parse_apply(input: &str, f: &dyn Fn(&[i32]) -> bool) -> usize {
-    input
-        .lines()
-        .map(|line| {
-            line.split_whitespace()
-                .map(|x| x.parse::<i32>().unwrap())
-                .collect_vec()
-        })
-        .filter(|line| f(line))
-        .count()
+fn count_valid(reports: &[Vec<i32>], f: &dyn Fn(&[i32]) -> bool) -> usize {
+    reports.iter().filter(|report| f(report)).count()
 }
 
 fn validate(v: &[i32]) -> bool {
+    if v.len() < 2 {
+        return true;
+    }
     let trend = Trend::new(v[0] < v[1]);
     v.iter().tuple_windows().all(|tuple| !trend.invalid(tuple))
 }
@@ -75,15 +90,28 @@ mod test {
 8 6 4 4 1
 1 3 6 7 9";
 
+    use super::parse;
+
     use super::part1;
     #[test]
     fn part1_test() {
-        assert_eq!(part1(EXAMPLE), 2);
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 2);
     }
 
     use super::part2;
     #[test]
     fn part2_test() {
-        assert_eq!(part2(EXAMPLE), 4);
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 4);
+        assert_eq!(part2(&parse("1 9\n5").unwrap()), 2);
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("7 6 4\n1 x 7").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(error.expected, "number");
+
+        let error = parse("7 6 4\n\n1").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
     }
 }
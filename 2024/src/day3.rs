@@ -14,7 +14,7 @@ fn parse_mul(s: &str) -> i32 {
     RE.captures_iter(s)
         .map(|c| c.extract())
         .map(|(_, [s1, s2])| s1.parse::<i32>().unwrap() * s2.parse::<i32>().unwrap())
-        .fold(0, |acc, x| x + acc)
+        .sum()
 }
 
 #[aoc(day03, part2)]
@@ -1,3 +1,4 @@
+use advent_of_code_common::parse::{lines, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 use std::collections::{HashMap, HashSet};
 
@@ -5,35 +6,32 @@ type Dependencies = HashMap<u32, HashSet<u32>>;
 type Lines = Vec<Vec<u32>>;
 
 #[aoc_generator(day5)]
-fn parse(input: &str) -> (Dependencies, Lines) {
+fn parse(input: &str) -> Result<(Dependencies, Lines), ParseError> {
     let mut dependencies = Dependencies::new();
+    let mut inputs = vec![];
+    let mut rules = true;
 
-    input
-        .lines()
-        .map(|line| line.split('|').collect::<Vec<&str>>())
-        .filter(|v| v.len() == 2)
-        .map(|parts| {
-            [
-                parts[0].parse::<u32>().unwrap(),
-                parts[1].parse::<u32>().unwrap(),
-            ]
-        })
-        .for_each(|[page, dependency]| {
-            dependencies.entry(page).or_default().insert(dependency);
-        });
-
-    let inputs = input
-        .lines()
-        .map(|line| line.split(',').collect::<Vec<&str>>())
-        .filter(|v| v.len() > 1)
-        .map(|line| {
-            line.iter()
-                .map(|&page| page.parse::<u32>().unwrap())
-                .collect()
-        })
-        .collect();
-
-    (dependencies, inputs)
+    for mut line in lines(input) {
+        if line.is_empty() {
+            rules = false;
+            continue;
+        }
+
+        if rules {
+            let page = line.number()?;
+            line.literal("|")?;
+            dependencies.entry(page).or_default().insert(line.number()?);
+        } else {
+            let mut pages = vec![line.number()?];
+            while line.eat(",") {
+                pages.push(line.number()?);
+            }
+            inputs.push(pages);
+        }
+        line.end()?;
+    }
+
+    Ok((dependencies, inputs))
 }
 
 #[aoc(day5, part1)]
@@ -131,11 +129,21 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse(EXAMPLE)), 143);
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 143);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse(EXAMPLE)), 123);
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 123);
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("47|53\n97|\n\n75,47").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+
+        let error = parse("47|53\n\n75,47,").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 7));
+        assert_eq!(error.expected, "number");
     }
 }
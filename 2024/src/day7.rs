@@ -1,5 +1,5 @@
+use advent_of_code_common::parse::{lines, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
-use itertools::Itertools;
 
 type BaseType = i64;
 type Line = (BaseType, Vec<BaseType>);
@@ -29,20 +29,22 @@ impl Node {
 }
 
 #[aoc_generator(day7)]
-fn parse(input: &str) -> Lines {
-    input
-        .lines()
-        .map(|line| line.split(':').collect_vec())
-        .map(|parts| {
-            (
-                parts[0].parse().unwrap(),
-                parts[1]
-                    .split_whitespace()
-                    .map(|element| element.parse().unwrap())
-                    .collect_vec(),
-            )
-        })
-        .collect_vec()
+fn parse(input: &str) -> Result<Lines, ParseError> {
+    let mut equations = vec![];
+
+    for mut line in lines(input) {
+        let result = line.number()?;
+        line.literal(":")?;
+        let mut operands = vec![];
+        line.skip_whitespace();
+        while !line.is_empty() {
+            operands.push(line.number()?);
+            line.skip_whitespace();
+        }
+        equations.push((result, operands));
+    }
+
+    Ok(equations)
 }
 
 #[aoc(day7, part1)]
@@ -151,11 +153,18 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse(EXAMPLE)), 3749);
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 3749);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse(EXAMPLE)), 11387);
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 11387);
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("190: 10 19\n3267 81 40 27").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 5));
+        assert_eq!(error.expected, "':'");
     }
 }
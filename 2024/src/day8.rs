@@ -1,16 +1,23 @@
+use advent_of_code_common::parse::{check_map, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 use std::collections::{BTreeMap, BTreeSet};
 
 type Position = (i32, i32);
 type Positions = BTreeMap<char, Vec<Position>>;
 
+#[derive(Debug)]
 struct Grid {
     size: i32,
     antennas: Vec<Vec<Position>>,
 }
 
 #[aoc_generator(day8)]
-fn parse(input: &str) -> Grid {
+fn parse(input: &str) -> Result<Grid, ParseError> {
+    check_map(
+        input,
+        |c| c == '.' || c.is_ascii_alphanumeric(),
+        "antenna or '.'",
+    )?;
     let mut positions: Positions = BTreeMap::new();
     let mut size = 0;
     input.lines().enumerate().for_each(|(y, line)| {
@@ -24,10 +31,10 @@ fn parse(input: &str) -> Grid {
             }
         })
     });
-    Grid {
+    Ok(Grid {
         size,
         antennas: positions.into_values().collect(),
-    }
+    })
 }
 
 #[aoc(day8, part1)]
@@ -110,11 +117,18 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse(EXAMPLE)), 14);
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 14);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse(EXAMPLE)), 34);
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 34);
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("..a.\n.#..").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+        assert_eq!(error.expected, "antenna or '.'");
     }
 }
@@ -1,23 +1,24 @@
+use advent_of_code_common::parse::{Cursor, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 
 #[aoc_generator(day9, part1)]
-fn parse(input: &str) -> Vec<i64> {
-    let mut chars = input.chars();
+fn parse(input: &str) -> Result<Vec<i64>, ParseError> {
+    let mut chars = Cursor::new(1, input);
     let mut blocks = vec![];
     let mut id = 0;
-    while let Some(block_space) = chars.next() {
-        for _ in 0..block_space.to_digit(10).unwrap() {
+    while !chars.is_empty() {
+        for _ in 0..chars.digit()? {
             blocks.push(id)
         }
-        if let Some(free_space) = chars.next() {
-            for _ in 0..free_space.to_digit(10).unwrap() {
+        if !chars.is_empty() {
+            for _ in 0..chars.digit()? {
                 blocks.push(-1)
             }
         }
         id += 1;
     }
 
-    blocks
+    Ok(blocks)
 }
 
 #[aoc(day9, part1)]
@@ -99,8 +100,8 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse(EXAMPLE)), 1928);
-        assert_eq!(part1(&parse("999")), 117);
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 1928);
+        assert_eq!(part1(&parse("999").unwrap()), 117);
     }
 
     #[test]
@@ -108,4 +109,11 @@ mod tests {
         assert_eq!(part2(EXAMPLE), 2858);
         assert_eq!(part2("99999"), 432);
     }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("23331x").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 6));
+        assert_eq!(error.expected, "digit");
+    }
 }
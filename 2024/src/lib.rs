@@ -18,4 +18,53 @@ extern crate aoc_runner;
 #[macro_use]
 extern crate aoc_runner_derive;
 
+use advent_of_code_common::solver::Solver;
+
+pub const SOLVERS: &[Solver] = &[
+    Solver::new(1, 1, Factory::day1_part1),
+    Solver::new(1, 2, Factory::day1_part2),
+    Solver::new(2, 1, Factory::day2_part1),
+    Solver::new(2, 2, Factory::day2_part2),
+    Solver::new(3, 1, Factory::day3_part1),
+    Solver::new(3, 2, Factory::day3_part2),
+    Solver::new(4, 1, Factory::day4_part1),
+    Solver::new(4, 2, Factory::day4_part2),
+    Solver::new(5, 1, Factory::day5_part1),
+    Solver::new(5, 2, Factory::day5_part2),
+    Solver::new(6, 1, Factory::day6_part1),
+    Solver::new(6, 2, Factory::day6_part2),
+    Solver::new(7, 1, Factory::day7_part1),
+    Solver::new(7, 2, Factory::day7_part2),
+    Solver::new(8, 1, Factory::day8_part1),
+    Solver::new(8, 2, Factory::day8_part2),
+    Solver::new(9, 1, Factory::day9_part1),
+    Solver::new(9, 2, Factory::day9_part2),
+    Solver::new(10, 1, Factory::day10_part1),
+    Solver::new(10, 2, Factory::day10_part2),
+    Solver::new(11, 1, Factory::day11_part1),
+    Solver::new(11, 2, Factory::day11_part2),
+    Solver::new(12, 1, Factory::day12_part1),
+    Solver::new(12, 2, Factory::day12_part2),
+    Solver::new(13, 1, Factory::day13_part1),
+    Solver::new(13, 2, Factory::day13_part2),
+    Solver::new(14, 1, Factory::day14_part1),
+    Solver::new(14, 2, Factory::day14_part2),
+    Solver::new(15, 1, Factory::day15_part1),
+];
+
 aoc_lib! { year = 2024 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use advent_of_code_common::solver;
+
+    use super::*;
+
+    #[test]
+    fn test_every_solver_listed() {
+        let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        assert_eq!(solver::unlisted(SOLVERS, &src).unwrap(), []);
+    }
+}
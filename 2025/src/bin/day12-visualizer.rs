@@ -4,7 +4,11 @@ use std::{
     time::Duration,
 };
 
-use advent_of_code_2025::day12::{Input, parse, place, try_place, unplace};
+use advent_of_code_2025::{
+    YEAR,
+    day12::{Input, parse, place, try_place, unplace},
+};
+use advent_of_code_common::inputs;
 use macroquad::{Window, prelude::*};
 
 enum Event {
@@ -202,8 +206,15 @@ fn instrumented_solve(
 }
 
 fn main() {
-    let input_str = include_str!("../../input/2025/day12.txt");
-    let input = parse(input_str);
+    let input_str =
+        inputs::load_for(env!("CARGO_MANIFEST_DIR"), None, YEAR, 12).unwrap_or_else(|e| {
+            eprintln!("{e}");
+            std::process::exit(1)
+        });
+    let input = parse(&input_str).unwrap_or_else(|e| {
+        eprintln!("{e}\n{}", e.snippet());
+        std::process::exit(1)
+    });
 
     let (tx, rx) = mpsc::channel::<Event>();
 
@@ -395,13 +406,7 @@ async fn render_loop(rx: Receiver<Event>) {
         draw_text(&status, 10.0, 25.0, 24.0, hud_color);
 
         if grid_w > 0 && grid_h > 0 {
-            draw_text(
-                &format!("{}x{}", grid_w, grid_h),
-                10.0,
-                48.0,
-                20.0,
-                DARKGRAY,
-            );
+            draw_text(format!("{}x{}", grid_w, grid_h), 10.0, 48.0, 20.0, DARKGRAY);
         }
 
         let fits_text = format!("Fits: {}", fit_count);
@@ -450,7 +455,7 @@ fn draw_sidebar(shape_previews: &[Vec<(i32, i32)>], counts: &[usize]) {
             color
         };
         draw_text(
-            &format!("x{}", count),
+            format!("x{}", count),
             SIDEBAR_X,
             y_cursor + (max_r as f32 * preview_cell) / 2.0 + 5.0,
             18.0,
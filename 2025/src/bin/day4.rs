@@ -0,0 +1,47 @@
+use std::{process::ExitCode, time::Instant};
+
+use advent_of_code_2025::{
+    YEAR,
+    day4::{BACKENDS, solve_with},
+};
+use advent_of_code_common::inputs;
+
+const USAGE: &str = "Usage: day4 [BACKEND...]
+
+Solves 2025 day 4 on each set backend (default: all of them) and checks they agree.
+Backends: bitset, std, ahash, foldhash, nohash, tinyset";
+
+fn main() -> ExitCode {
+    let mut backends: Vec<String> = std::env::args().skip(1).collect();
+    if backends.is_empty() {
+        backends = BACKENDS.iter().map(|b| b.to_string()).collect();
+    }
+
+    if let Some(unknown) = backends.iter().find(|b| !BACKENDS.contains(&b.as_str())) {
+        eprintln!("unknown backend '{unknown}'\n\n{USAGE}");
+        return ExitCode::from(2);
+    }
+
+    let input = inputs::load_for(env!("CARGO_MANIFEST_DIR"), None, YEAR, 4).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1)
+    });
+
+    let mut answers = vec![];
+    for backend in &backends {
+        let start = Instant::now();
+        let (part1, part2) = solve_with(backend, &input).unwrap();
+        println!(
+            "{backend:<10} part 1: {part1:<8} part 2: {part2:<8} ({:.1?})",
+            start.elapsed()
+        );
+        answers.push((part1, part2));
+    }
+
+    if answers.windows(2).any(|pair| pair[0] != pair[1]) {
+        eprintln!("The backends disagree");
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
@@ -4,7 +4,11 @@ use std::{
     time::Duration,
 };
 
-use advent_of_code_2025::day9::{Tile, parse, perimeter};
+use advent_of_code_2025::{
+    YEAR,
+    day9::{Tile, parse, perimeter},
+};
+use advent_of_code_common::inputs;
 use foldhash::HashSet;
 use macroquad::{Window, prelude::*};
 use std::sync::mpsc::{self, Receiver};
@@ -53,9 +57,15 @@ impl From<Message> for Draw {
 }
 
 fn main() {
-    let input = include_str!("../../input/2025/day9.txt");
+    let input = inputs::load_for(env!("CARGO_MANIFEST_DIR"), None, YEAR, 9).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1)
+    });
 
-    let tiles = parse(input);
+    let tiles = parse(&input).unwrap_or_else(|e| {
+        eprintln!("{e}\n{}", e.snippet());
+        std::process::exit(1)
+    });
     let red_tiles = tiles
         .iter()
         .map(|t| ((t.x / RATE) as f32, (t.y / RATE) as f32))
@@ -1,21 +1,19 @@
+use advent_of_code_common::parse::{ParseError, lines};
 use aoc_runner_derive::{aoc, aoc_generator};
 
 #[aoc_generator(day1)]
-fn parse(input: &str) -> Vec<i16> {
+fn parse(input: &str) -> Result<Vec<i16>, ParseError> {
     let mut rotations = vec![];
 
-    for line in input.lines() {
-        let direction = &line[0..1];
-        let &value = &line[1..].parse::<i16>().unwrap();
+    for mut line in lines(input) {
+        let direction = line.one_of(&[("L", -1), ("R", 1)])?;
+        let value: i16 = line.number()?;
+        line.end()?;
 
-        if direction == "L" {
-            rotations.push(-value)
-        } else {
-            rotations.push(value)
-        }
+        rotations.push(direction * value)
     }
 
-    rotations
+    Ok(rotations)
 }
 
 #[aoc(day1, part1)]
@@ -69,11 +67,18 @@ L82"#;
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse(INPUT)), 3);
+        assert_eq!(part1(&parse(INPUT).unwrap()), 3);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse(INPUT)), 6);
+        assert_eq!(part2(&parse(INPUT).unwrap()), 6);
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("L68\nX30").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(error.expected, "one of 'L', 'R'");
     }
 }
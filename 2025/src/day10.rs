@@ -1,40 +1,55 @@
 use itertools::Itertools;
 use std::ops::AddAssign;
 
+use advent_of_code_common::parse::{ParseError, lines};
 use aoc_runner_derive::{aoc, aoc_generator};
 use foldhash::{HashMap, HashMapExt};
 
 #[aoc_generator(day10)]
-fn parse(input: &str) -> Vec<Machine> {
+fn parse(input: &str) -> Result<Vec<Machine>, ParseError> {
     let mut machines = Vec::new();
 
-    for line in input.lines() {
-        let mut parts = line.split_whitespace();
-
-        let target = parts
-            .next()
-            .unwrap()
+    for mut line in lines(input) {
+        line.literal("[")?;
+        let lights = line.clone();
+        let pattern = line.take_while(|c| c == '.' || c == '#');
+        if pattern.len() > 16 {
+            return Err(lights.error("at most 16 lights"));
+        }
+        let target = pattern
             .chars()
-            .filter(|c| *c != '[' && *c != ']')
             .map(|c| c == '#')
             .enumerate()
             .fold(0, |acc, (i, n)| acc | (n as u16) << i);
-        let joltages = parts
-            .next_back()
-            .unwrap()
-            .trim_matches(|c| c == '{' || c == '}')
-            .split(',')
-            .map(|j| j.parse::<u16>().unwrap())
-            .collect();
-        let buttons = parts
-            .take_while(|x| x.starts_with('('))
-            .map(|b| {
-                b[1..b.len() - 1]
-                    .split(',')
-                    .map(|c| c.parse::<u16>().unwrap())
-                    .fold(0, |acc, n| acc | (1 << n))
-            })
-            .collect();
+        line.literal("]")?;
+        line.skip_whitespace();
+
+        let mut buttons = vec![];
+        while line.eat("(") {
+            let mut button = 0;
+            loop {
+                let light = line.clone();
+                let n: usize = line.number()?;
+                if n >= pattern.len() {
+                    return Err(light.error(format!("light below {}", pattern.len())));
+                }
+                button |= 1 << n;
+                if !line.eat(",") {
+                    break;
+                }
+            }
+            line.literal(")")?;
+            line.skip_whitespace();
+            buttons.push(button);
+        }
+
+        line.literal("{")?;
+        let mut joltages = vec![line.number()?];
+        while line.eat(",") {
+            joltages.push(line.number()?);
+        }
+        line.literal("}")?;
+        line.end()?;
 
         machines.push(Machine {
             target,
@@ -43,7 +58,7 @@ fn parse(input: &str) -> Vec<Machine> {
         })
     }
 
-    machines
+    Ok(machines)
 }
 
 #[aoc(day10, part1)]
@@ -181,11 +196,25 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse(INPUT)), 7);
+        assert_eq!(part1(&parse(INPUT).unwrap()), 7);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse(INPUT)), 33);
+        assert_eq!(part2(&parse(INPUT).unwrap()), 33);
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 46));
+        assert_eq!(error.expected, "'}'");
+
+        let error = parse("[.##.] (3) (1,4) {3,5,4,7}").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 15));
+        assert_eq!(
+            error.snippet(),
+            "1 | [.##.] (3) (1,4) {3,5,4,7}\n  |               ^ expected light below 4"
+        );
     }
 }
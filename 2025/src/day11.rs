@@ -1,20 +1,25 @@
+use advent_of_code_common::parse::{ParseError, lines};
 use aoc_runner_derive::{aoc, aoc_generator};
 use foldhash::{HashMap, HashMapExt};
 
 #[aoc_generator(day11)]
-fn parse(input: &str) -> HashMap<String, Vec<String>> {
+fn parse(input: &str) -> Result<HashMap<String, Vec<String>>, ParseError> {
     let mut graph: HashMap<String, Vec<String>> = HashMap::new();
-    for line in input.lines() {
-        let (key, rest) = line.split_once(':').unwrap();
-        for target in rest.split_whitespace() {
+    for mut line in lines(input) {
+        let key = line.word()?;
+        line.literal(":")?;
+        line.skip_whitespace();
+        while !line.is_empty() {
+            let target = line.word()?;
             graph
                 .entry(key.to_string())
                 .or_default()
                 .push(target.into());
+            line.skip_whitespace();
         }
     }
 
-    graph
+    Ok(graph)
 }
 
 fn count_paths(
@@ -71,8 +76,9 @@ mod tests {
     #[test]
     fn part1_example() {
         assert_eq!(
-            part1(&parse(
-                r#"aaa: you hhh
+            part1(
+                &parse(
+                    r#"aaa: you hhh
 you: bbb ccc
 bbb: ddd eee
 ccc: ddd eee fff
@@ -82,7 +88,9 @@ fff: out
 ggg: out
 hhh: ccc fff iii
 iii: out"#
-            )),
+                )
+                .unwrap()
+            ),
             5
         );
     }
@@ -90,8 +98,9 @@ iii: out"#
     #[test]
     fn part2_example() {
         assert_eq!(
-            part2(&parse(
-                r#"svr: aaa bbb
+            part2(
+                &parse(
+                    r#"svr: aaa bbb
 aaa: fft
 fft: ccc
 bbb: tty
@@ -104,8 +113,17 @@ dac: fff
 fff: ggg hhh
 ggg: out
 hhh: out"#
-            )),
+                )
+                .unwrap()
+            ),
             2
         );
     }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("aaa: you hhh\nyou bbb").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(error.expected, "':'");
+    }
 }
@@ -1,3 +1,4 @@
+use advent_of_code_common::parse::{ParseError, lines};
 use aoc_runner_derive::{aoc, aoc_generator};
 
 pub struct Input {
@@ -52,24 +53,31 @@ pub fn relative_to_anchor(cells: &[(i32, i32)]) -> Vec<(i32, i32)> {
 }
 
 #[aoc_generator(day12)]
-pub fn parse(input: &str) -> Input {
+pub fn parse(input: &str) -> Result<Input, ParseError> {
     let mut shapes_raw: Vec<Vec<(i32, i32)>> = Vec::new();
     let mut regions = Vec::new();
     let mut current_shape_lines: Vec<&str> = Vec::new();
     let mut in_shape = false;
 
-    for line in input.lines() {
+    for mut cursor in lines(input) {
+        let line = cursor.rest().trim_end();
         if line.contains('x') && line.contains(':') {
-            let (dims, counts_str) = line.split_once(':').unwrap();
-            let (w, h) = dims.trim().split_once('x').unwrap();
-            let w: usize = w.parse().unwrap();
-            let h: usize = h.parse().unwrap();
-            let counts: Vec<usize> = counts_str
-                .split_whitespace()
-                .map(|n| n.parse().unwrap())
-                .collect();
+            let w: usize = cursor.number()?;
+            cursor.literal("x")?;
+            let h: usize = cursor.number()?;
+            cursor.literal(":")?;
+            let mut counts: Vec<usize> = vec![];
+            cursor.skip_whitespace();
+            while !cursor.is_empty() {
+                counts.push(cursor.number()?);
+                cursor.skip_whitespace();
+            }
             regions.push((w, h, counts));
-        } else if line.contains(':') && line.trim().ends_with(':') {
+        } else if line.ends_with(':') {
+            cursor.number::<usize>()?;
+            cursor.literal(":")?;
+            cursor.end()?;
+
             if in_shape && !current_shape_lines.is_empty() {
                 shapes_raw.push(parse_shape(&current_shape_lines));
                 current_shape_lines.clear();
@@ -77,11 +85,17 @@ pub fn parse(input: &str) -> Input {
             in_shape = true;
             current_shape_lines.clear();
         } else if in_shape && !line.is_empty() {
+            cursor.take_while(|c| c == '#' || c == '.');
+            if !cursor.is_empty() {
+                return Err(cursor.error("one of '#', '.'"));
+            }
             current_shape_lines.push(line);
         } else if line.is_empty() && in_shape && !current_shape_lines.is_empty() {
             shapes_raw.push(parse_shape(&current_shape_lines));
             current_shape_lines.clear();
             in_shape = false;
+        } else if !line.is_empty() {
+            return Err(cursor.error("a shape index or a region"));
         }
     }
     if in_shape && !current_shape_lines.is_empty() {
@@ -99,11 +113,11 @@ pub fn parse(input: &str) -> Input {
         })
         .collect();
 
-    Input {
+    Ok(Input {
         shapes,
         regions,
         shape_cells,
-    }
+    })
 }
 
 pub fn parse_shape(lines: &[&str]) -> Vec<(i32, i32)> {
@@ -317,6 +331,20 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse(INPUT)), 2);
+        assert_eq!(part1(&parse(INPUT).unwrap()), 2);
+    }
+
+    #[test]
+    fn parse_error() {
+        let Err(error) = parse("0:\n##.\n#o#\n\n4x4: 1") else {
+            panic!("an unknown cell should not parse")
+        };
+        assert_eq!((error.line, error.column), (3, 2));
+
+        let Err(error) = parse("0:\n##.\n\n4x: 1") else {
+            panic!("a region without height should not parse")
+        };
+        assert_eq!((error.line, error.column), (4, 3));
+        assert_eq!(error.expected, "number");
     }
 }
@@ -1,18 +1,23 @@
+use advent_of_code_common::parse::{Cursor, ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
 #[aoc_generator(day2)]
-fn parse(input: &str) -> Vec<(u64, u64)> {
+fn parse(input: &str) -> Result<Vec<(u64, u64)>, ParseError> {
     let mut ranges = vec![];
-    let parts = input.split(',');
+    let mut line = Cursor::new(1, input);
 
-    for part in parts {
-        let mut range = part.split('-');
-        let start: u64 = range.next().unwrap().parse().unwrap();
-        let end: u64 = range.next().unwrap().parse().unwrap();
+    loop {
+        let start: u64 = line.number()?;
+        line.literal("-")?;
+        let end: u64 = line.number()?;
 
         ranges.push((start, end));
+        if !line.eat(",") {
+            break;
+        }
     }
+    line.end()?;
 
-    ranges
+    Ok(ranges)
 }
 
 fn is_valid_part1(n: u64) -> bool {
@@ -77,7 +82,8 @@ mod tests {
         assert_eq!(
             part1(&parse(
                 "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124"
-            )),
+            )
+            .unwrap()),
             1227775554
         );
     }
@@ -87,8 +93,16 @@ mod tests {
         assert_eq!(
             part2(&parse(
                 "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124"
-            )),
+            )
+            .unwrap()),
             4174379265
         );
     }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("11-22,95-").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 10));
+        assert_eq!(error.expected, "number");
+    }
 }
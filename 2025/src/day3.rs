@@ -1,18 +1,19 @@
+use advent_of_code_common::parse::{ParseError, lines};
 use aoc_runner_derive::{aoc, aoc_generator};
 
 #[aoc_generator(day3)]
-fn parse(input: &str) -> Vec<Vec<u64>> {
+fn parse(input: &str) -> Result<Vec<Vec<u64>>, ParseError> {
     let mut batteries = vec![];
 
-    for line in input.lines() {
-        batteries.push(
-            line.chars()
-                .map(|c| u64::from(c.to_digit(10).unwrap()))
-                .collect(),
-        )
+    for mut line in lines(input) {
+        let mut bank = vec![];
+        while !line.is_empty() {
+            bank.push(u64::from(line.digit()?));
+        }
+        batteries.push(bank)
     }
 
-    batteries
+    Ok(batteries)
 }
 
 fn get_jolts(input: &[Vec<u64>], digits: usize) -> u64 {
@@ -61,11 +62,18 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse(INPUT)), 357);
+        assert_eq!(part1(&parse(INPUT).unwrap()), 357);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse(INPUT)), 3121910778619);
+        assert_eq!(part2(&parse(INPUT).unwrap()), 3121910778619);
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("987654321111111\n81111111111111a").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 15));
+        assert_eq!(error.expected, "digit");
     }
 }
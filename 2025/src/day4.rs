@@ -1,81 +1,279 @@
+use std::{
+    borrow::Borrow,
+    collections::HashSet,
+    error::Error,
+    fmt::Display,
+    hash::{BuildHasher, RandomState},
+};
+
+use aoc_runner::{ArcStr, Runner};
 use aoc_runner_derive::aoc;
+use grid::{Grid, Position};
+use nohash::IntSet;
+use tinyset::Set64;
+
+/// The set operations the roll removal needs, so that every backend runs the same code
+pub trait RollSet: Clone {
+    /// An empty set for a map of `width` by `height` cells
+    fn with_size(width: usize, height: usize) -> Self;
+    fn insert(&mut self, roll: Position);
+    fn remove(&mut self, roll: Position);
+    fn contains(&self, roll: Position) -> bool;
+    fn iter(&self) -> impl Iterator<Item = Position> + '_;
+}
+
+impl<S: BuildHasher + Default + Clone> RollSet for HashSet<Position, S> {
+    fn with_size(_: usize, _: usize) -> Self {
+        HashSet::with_hasher(S::default())
+    }
+
+    fn insert(&mut self, roll: Position) {
+        HashSet::insert(self, roll);
+    }
+
+    fn remove(&mut self, roll: Position) {
+        HashSet::remove(self, &roll);
+    }
+
+    fn contains(&self, roll: Position) -> bool {
+        HashSet::contains(self, &roll)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = Position> + '_ {
+        HashSet::iter(self).copied()
+    }
+}
+
+/// Positions kept as their index in the map, row by row, for the sets that only hold integers
+#[derive(Clone)]
+pub struct Indexed<S> {
+    width: usize,
+    indices: S,
+}
+
+impl<S> Indexed<S> {
+    fn index(&self, (x, y): Position) -> u64 {
+        (y * self.width + x) as u64
+    }
+
+    fn position(&self, index: u64) -> Position {
+        let index = index as usize;
+        (index % self.width, index / self.width)
+    }
+}
+
+impl RollSet for Indexed<IntSet<u64>> {
+    fn with_size(width: usize, _: usize) -> Self {
+        Indexed {
+            width,
+            indices: IntSet::default(),
+        }
+    }
+
+    fn insert(&mut self, roll: Position) {
+        self.indices.insert(self.index(roll));
+    }
 
-fn parse(input: &str) -> Vec<Vec<bool>> {
-    let mut grid = vec![];
-
-    for line in input.lines() {
-        grid.push(line.chars().map(|c| c == '@').collect())
-    }
-
-    grid
-}
-
-fn get_acceseable(rolls: &[Vec<bool>]) -> Vec<(usize, usize)> {
-    let height = rolls.len();
-    let width = rolls[0].len();
-
-    let mut acceseables = vec![];
-    let around: [(i16, i16); 8] = [
-        (-1, 0),
-        (-1, -1),
-        (0, -1),
-        (1, -1),
-        (1, 0),
-        (1, 1),
-        (0, 1),
-        (-1, 1),
-    ];
-
-    for (y, row) in rolls.iter().enumerate() {
-        for (x, &is_roll) in row.iter().enumerate() {
-            if !is_roll {
-                continue;
-            }
-
-            let mut neighbors = 0;
-
-            for (dx, dy) in around {
-                let (x, y) = (x as i16, y as i16);
-
-                if x + dx >= 0
-                    && x + dx < width as i16
-                    && y + dy >= 0
-                    && y + dy < height as i16
-                    && rolls[(y + dy) as usize][(x + dx) as usize]
-                {
-                    neighbors += 1
-                }
-            }
-            if neighbors < 4 {
-                acceseables.push((x, y));
-            }
+    fn remove(&mut self, roll: Position) {
+        self.indices.remove(&self.index(roll));
+    }
+
+    fn contains(&self, roll: Position) -> bool {
+        self.indices.contains(&self.index(roll))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = Position> + '_ {
+        self.indices.iter().map(|&index| self.position(index))
+    }
+}
+
+impl RollSet for Indexed<Set64<u64>> {
+    fn with_size(width: usize, _: usize) -> Self {
+        Indexed {
+            width,
+            indices: Set64::new(),
         }
     }
 
-    acceseables
+    fn insert(&mut self, roll: Position) {
+        self.indices.insert(self.index(roll));
+    }
+
+    fn remove(&mut self, roll: Position) {
+        self.indices.remove(&self.index(roll));
+    }
+
+    fn contains(&self, roll: Position) -> bool {
+        self.indices.contains(self.index(roll))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = Position> + '_ {
+        self.indices.iter().map(|index| self.position(index))
+    }
 }
 
-#[aoc(day4, part1)]
-fn part1(input: &str) -> u16 {
-    get_acceseable(&parse(input)).len() as u16
+/// One bit per cell of the map, row by row
+#[derive(Clone)]
+pub struct BitSet {
+    width: usize,
+    words: Vec<u64>,
 }
 
-#[aoc(day4, part2)]
-fn part2(input: &str) -> u16 {
-    let mut grid = parse(input);
+impl BitSet {
+    fn index(&self, (x, y): Position) -> (usize, u64) {
+        let i = y * self.width + x;
+        (i / 64, 1 << (i % 64))
+    }
+}
+
+impl RollSet for BitSet {
+    fn with_size(width: usize, height: usize) -> Self {
+        BitSet {
+            width,
+            words: vec![0; (width * height).div_ceil(64)],
+        }
+    }
+
+    fn insert(&mut self, roll: Position) {
+        let (word, bit) = self.index(roll);
+        self.words[word] |= bit;
+    }
+
+    fn remove(&mut self, roll: Position) {
+        let (word, bit) = self.index(roll);
+        self.words[word] &= !bit;
+    }
+
+    fn contains(&self, roll: Position) -> bool {
+        let (word, bit) = self.index(roll);
+        self.words[word] & bit != 0
+    }
+
+    fn iter(&self) -> impl Iterator<Item = Position> + '_ {
+        self.words.iter().enumerate().flat_map(move |(i, &word)| {
+            (0..64)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| {
+                    let index = i * 64 + bit;
+                    (index % self.width, index / self.width)
+                })
+        })
+    }
+}
+
+pub type StdSet = HashSet<Position, RandomState>;
+pub type AHashSet = HashSet<Position, ahash::RandomState>;
+pub type FoldHashSet = HashSet<Position, foldhash::fast::RandomState>;
+/// Hashes are the indices themselves, whose empty high bits make hashbrown's probing slow
+pub type NoHashSet = Indexed<IntSet<u64>>;
+pub type TinySet = Indexed<Set64<u64>>;
+
+/// The backend names accepted by [`solve_with`], the first one being the `#[aoc]` solver's
+pub const BACKENDS: &[&str] = &["bitset", "std", "ahash", "foldhash", "nohash", "tinyset"];
+
+/// The rolls left on a map, held in the set `S`
+#[derive(Clone)]
+pub struct Rolls<S> {
+    /// The map as read, whose bounds the neighbours are looked up in
+    map: Grid<bool>,
+    set: S,
+}
+
+pub fn parse<S: RollSet>(input: &str) -> Rolls<S> {
+    let map = Grid::parse(input, |c| c == '@');
+    let mut set = S::with_size(map.width(), map.height());
+
+    for (position, _) in map.iter().filter(|&(_, &roll)| roll) {
+        set.insert(position);
+    }
+
+    Rolls { map, set }
+}
+
+fn accessible<S: RollSet>(rolls: &Rolls<S>) -> Vec<Position> {
+    rolls
+        .set
+        .iter()
+        .filter(|&roll| {
+            rolls
+                .map
+                .neighbours8(roll)
+                .filter(|&neighbour| rolls.set.contains(neighbour))
+                .count()
+                < 4
+        })
+        .collect()
+}
+
+pub fn removable<S: RollSet>(rolls: &Rolls<S>) -> usize {
+    let mut rolls = rolls.clone();
     let mut acc = 0;
 
-    while let next = get_acceseable(&grid)
+    while let next = accessible(&rolls)
         && !next.is_empty()
     {
         acc += next.len();
 
-        for (x, y) in next.into_iter() {
-            grid[y][x] = false;
+        for roll in next {
+            rolls.set.remove(roll);
         }
     }
 
-    acc as u16
+    acc
+}
+
+#[aoc(day4, part1)]
+fn part1(input: &str) -> usize {
+    accessible(&parse::<BitSet>(input)).len()
+}
+
+#[aoc(day4, part2)]
+fn part2(input: &str) -> usize {
+    removable(&parse::<BitSet>(input))
+}
+
+/// Both answers on the backend called `backend`, if there is one by that name
+pub fn solve_with(backend: &str, input: &str) -> Option<(usize, usize)> {
+    fn both<S: RollSet>(input: &str) -> (usize, usize) {
+        let rolls = parse::<S>(input);
+        (accessible(&rolls).len(), removable(&rolls))
+    }
+
+    Some(match backend {
+        "bitset" => both::<BitSet>(input),
+        "std" => both::<StdSet>(input),
+        "ahash" => both::<AHashSet>(input),
+        "foldhash" => both::<FoldHashSet>(input),
+        "nohash" => both::<NoHashSet>(input),
+        "tinyset" => both::<TinySet>(input),
+        _ => return None,
+    })
+}
+
+/// Day 4 on the backend `S`, benched as a variant of the `#[aoc]` solver
+pub struct OnBackend<S, const PART: u8>(Rolls<S>);
+
+impl<S: RollSet, const PART: u8> Runner for OnBackend<S, PART> {
+    fn r#gen(input: ArcStr) -> Self {
+        OnBackend(parse(input.borrow()))
+    }
+
+    fn run(&self) -> Box<dyn Display> {
+        match PART {
+            1 => Box::new(accessible(&self.0).len()),
+            _ => Box::new(removable(&self.0)),
+        }
+    }
+
+    fn bench(&self, black_box: fn(&dyn Display)) {
+        black_box(&self.run())
+    }
+}
+
+pub fn on_backend<S: RollSet + 'static, const PART: u8>(
+    input: ArcStr,
+) -> Result<Box<dyn Runner>, Box<dyn Error>> {
+    Ok(Box::new(OnBackend::<S, PART>::r#gen(input)))
 }
 
 #[cfg(test)]
@@ -102,4 +300,40 @@ mod tests {
     fn part2_example() {
         assert_eq!(part2(INPUT), 43);
     }
+
+    #[test]
+    fn test_backends() {
+        for backend in BACKENDS {
+            assert_eq!(solve_with(backend, INPUT), Some((13, 43)), "{backend}");
+        }
+        assert_eq!(solve_with("btree", INPUT), None);
+    }
+
+    #[test]
+    fn test_bitset() {
+        let mut rolls = BitSet::with_size(70, 3);
+        for roll in [(0, 0), (69, 0), (2, 2)] {
+            rolls.insert(roll);
+        }
+        rolls.remove((69, 0));
+
+        assert!(rolls.contains((2, 2)));
+        assert!(!rolls.contains((69, 0)));
+        assert_eq!(rolls.iter().collect::<Vec<_>>(), [(0, 0), (2, 2)]);
+    }
+
+    #[test]
+    fn test_indexed() {
+        let mut rolls = TinySet::with_size(70, 3);
+        for roll in [(69, 0), (0, 1), (69, 2)] {
+            rolls.insert(roll);
+        }
+        rolls.remove((0, 1));
+
+        assert!(rolls.contains((69, 2)));
+        assert!(!rolls.contains((0, 1)));
+        let mut found: Vec<Position> = rolls.iter().collect();
+        found.sort_unstable();
+        assert_eq!(found, [(69, 0), (69, 2)]);
+    }
 }
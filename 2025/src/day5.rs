@@ -1,36 +1,47 @@
-use aoc_runner_derive::aoc;
+use advent_of_code_common::parse::{ParseError, lines};
+use aoc_runner_derive::{aoc, aoc_generator};
+
+#[derive(Debug)]
+struct Inventory {
+    /// The fresh ranges, sorted
+    ranges: Vec<(u64, u64)>,
+    /// The available ids, listed after a blank line
+    ids: Vec<u64>,
+}
 
-fn parse(input: &str, all: bool) -> (Vec<(u64, u64)>, Vec<u64>) {
+#[aoc_generator(day5)]
+fn parse(input: &str) -> Result<Inventory, ParseError> {
     let mut ranges = vec![];
-    let mut lines = input.lines();
+    let mut lines = lines(input);
 
-    while let line = lines.next().unwrap()
-        && !line.is_empty()
-    {
-        let (s, e) = line.split_once('-').unwrap();
+    for mut line in lines.by_ref() {
+        if line.is_empty() {
+            break;
+        }
 
-        ranges.push((s.parse().unwrap(), e.parse().unwrap()))
+        let start = line.number()?;
+        line.literal("-")?;
+        let end = line.number()?;
+        line.end()?;
+        ranges.push((start, end))
     }
 
     ranges.sort();
-    if !all {
-        return (ranges, vec![]);
-    }
 
     let mut ids = vec![];
-    for line in lines {
-        ids.push(line.parse().unwrap())
+    for mut line in lines {
+        ids.push(line.number()?);
+        line.end()?;
     }
 
-    (ranges, ids)
+    Ok(Inventory { ranges, ids })
 }
 
 #[aoc(day5, part1)]
-fn part1(input: &str) -> u64 {
-    let (ranges, ids) = parse(input, true);
+fn part1(Inventory { ranges, ids }: &Inventory) -> u64 {
     let mut count = 0;
 
-    for i in ids {
+    for &i in ids {
         for (s, e) in ranges.iter() {
             // range is sorted
             if *s > i {
@@ -48,11 +59,10 @@ fn part1(input: &str) -> u64 {
 }
 
 #[aoc(day5, part2)]
-fn part2(input: &str) -> u64 {
-    let (ranges, _) = parse(input, false);
+fn part2(Inventory { ranges, .. }: &Inventory) -> u64 {
     let (mut count, mut last) = (0, 0);
 
-    for (s, e) in ranges.into_iter() {
+    for &(s, e) in ranges {
         if s > last && e > last {
             count += e - s + 1;
             last = e;
@@ -83,11 +93,26 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(INPUT), 3);
+        assert_eq!(part1(&parse(INPUT).unwrap()), 3);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(INPUT), 14);
+        assert_eq!(part2(&parse(INPUT).unwrap()), 14);
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("3-5\n10-\n\n1").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(error.expected, "number");
+
+        let error = parse("3-5\n1\n\n1").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+        assert_eq!(error.expected, "'-'");
+
+        let error = parse("3-5\n\n1\n5x").unwrap_err();
+        assert_eq!((error.line, error.column), (4, 2));
+        assert_eq!(error.expected, "end of line");
     }
 }
@@ -1,100 +1,138 @@
+use std::{iter, ops::Range};
+
+use advent_of_code_common::parse::{ParseError, lines};
 use aoc_runner_derive::{aoc, aoc_generator};
+use grid::Grid;
 
-struct Col {
-    values: Vec<String>,
+struct Problem {
     operation: char,
-    width: usize,
+    columns: Range<usize>,
 }
 
-impl Col {
-    fn new(operation: char, width: usize) -> Col {
-        Col {
-            values: vec![],
-            operation,
-            width,
-        }
-    }
-
-    fn add(&mut self, value: String) {
-        self.values.push(value);
-    }
-
-    fn compute(&self) -> u64 {
+impl Problem {
+    fn compute(&self, numbers: impl Iterator<Item = u64>) -> u64 {
         if self.operation == '+' {
-            self.values
-                .iter()
-                .map(|s| s.trim().parse::<u64>().unwrap())
-                .sum()
+            numbers.sum()
         } else {
-            self.values
-                .iter()
-                .fold(1, |acc, v| acc * v.trim().parse::<u64>().unwrap())
+            numbers.product()
         }
     }
+}
 
-    fn transpose(&self) -> Self {
-        let mut result = vec![];
-        result.resize(self.width, String::new());
-
-        for (i, r) in result.iter_mut().enumerate() {
-            for v in self.values.iter() {
-                let c = v.chars().nth(i).unwrap();
-
-                if c != ' ' {
-                    r.push(c)
-                }
-            }
-        }
-
-        for r in result.iter_mut() {
-            *r = r.chars().collect()
-        }
-
-        Col {
-            width: result.len(),
-            values: result,
-            ..*self
-        }
-    }
+struct Worksheet {
+    cells: Grid<char>,
+    problems: Vec<Problem>,
 }
 
 #[aoc_generator(day6)]
-fn parse(input: &str) -> Vec<Col> {
-    let mut cols = vec![];
-    let mut lines = input.lines().rev();
-
-    let last_line = lines.next().unwrap();
-    let mut op = last_line[0..1].chars().next().unwrap();
-    let mut start = 0;
-
-    for (end, current) in last_line.chars().enumerate().skip(1) {
-        if current != ' ' {
-            cols.push(Col::new(op, end - start - 1));
-            op = current;
-            start = end;
+fn parse(input: &str) -> Result<Worksheet, ParseError> {
+    let rows = input.lines().count();
+    if rows == 0 {
+        return Err(ParseError::new(1, 1, "rows of numbers", ""));
+    }
+    for mut line in lines(input) {
+        if line.line() < rows {
+            line.take_while(|c| c.is_ascii_digit() || c == ' ');
+            if !line.is_empty() {
+                return Err(line.error("digit"));
+            }
+        } else {
+            line.take_while(|c| c == '+' || c == '*' || c == ' ');
+            if !line.is_empty() {
+                return Err(line.error("one of '+', '*'"));
+            }
         }
     }
-    cols.push(Col::new(op, last_line.len() - start));
 
-    for line in lines.rev() {
-        let mut start = 0;
-        for col in cols.iter_mut() {
-            col.add(line[start..start + col.width].to_string());
-            start += col.width + 1;
+    let width = input.lines().map(str::len).max().unwrap_or(0);
+    let cells = Grid::from_rows(
+        input
+            .lines()
+            .map(|line| line.chars().chain(iter::repeat(' ')).take(width)),
+    );
+
+    let operations = cells.row(cells.height() - 1);
+    let starts: Vec<usize> = (0..width).filter(|&x| operations[x] != ' ').collect();
+
+    let problems: Vec<Problem> = starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| Problem {
+            operation: operations[start],
+            columns: start..starts.get(i + 1).map_or(width, |next| next - 1),
+        })
+        .collect();
+
+    // The parts read each problem as numbers across its rows, or down its columns
+    let texts: Vec<&str> = input.lines().collect();
+    for problem in &problems {
+        for (y, text) in texts[..rows - 1].iter().enumerate() {
+            let cell: String = cells.row(y)[problem.columns.clone()].iter().collect();
+            if cell.trim().parse::<u64>().is_err() {
+                let column = problem.columns.start + cell.find(|c| c != ' ').unwrap_or(0);
+                return Err(ParseError::new(y + 1, column + 1, "number", text));
+            }
+        }
+        for x in problem.columns.clone() {
+            let digits: String = cells
+                .column(x)
+                .take(rows - 1)
+                .filter(|&&c| c != ' ')
+                .collect();
+            if digits.parse::<u64>().is_err() {
+                return Err(ParseError::new(
+                    1,
+                    x + 1,
+                    "a number down the column",
+                    texts[0],
+                ));
+            }
         }
     }
 
-    cols
+    Ok(Worksheet { cells, problems })
 }
 
 #[aoc(day6, part1)]
-fn part1(input: &[Col]) -> u64 {
-    input.iter().map(Col::compute).sum()
+fn part1(input: &Worksheet) -> u64 {
+    let rows = input.cells.height() - 1;
+
+    input
+        .problems
+        .iter()
+        .map(|problem| {
+            problem.compute((0..rows).map(|y| {
+                input.cells.row(y)[problem.columns.clone()]
+                    .iter()
+                    .collect::<String>()
+                    .trim()
+                    .parse::<u64>()
+                    .unwrap()
+            }))
+        })
+        .sum()
 }
 
 #[aoc(day6, part2)]
-fn part2(input: &[Col]) -> u64 {
-    input.iter().map(|c| c.transpose().compute()).sum()
+fn part2(input: &Worksheet) -> u64 {
+    let rows = input.cells.height() - 1;
+
+    input
+        .problems
+        .iter()
+        .map(|problem| {
+            problem.compute(problem.columns.clone().map(|x| {
+                input
+                    .cells
+                    .column(x)
+                    .take(rows)
+                    .filter(|&&c| c != ' ')
+                    .collect::<String>()
+                    .parse::<u64>()
+                    .unwrap()
+            }))
+        })
+        .sum()
 }
 
 #[cfg(test)]
@@ -108,11 +146,39 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse(INPUT)), 4277556);
+        assert_eq!(part1(&parse(INPUT).unwrap()), 4277556);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse(INPUT)), 3263827);
+        assert_eq!(part2(&parse(INPUT).unwrap()), 3263827);
+    }
+
+    #[test]
+    fn parse_error() {
+        let Err(error) = parse("123 328\n 45 6x\n*   +") else {
+            panic!("a letter should not parse")
+        };
+        assert_eq!((error.line, error.column), (2, 6));
+
+        let Err(error) = parse("123 328\n*   -") else {
+            panic!("an unknown operation should not parse")
+        };
+        assert_eq!((error.line, error.column), (2, 5));
+        assert_eq!(error.expected, "one of '+', '*'");
+
+        let Err(error) = parse("123 328\n 45\n*   +") else {
+            panic!("a missing operand should not parse")
+        };
+        assert_eq!(
+            (error.line, error.column, error.expected.as_str()),
+            (2, 5, "number")
+        );
+
+        let Err(error) = parse("1  3\n 4  5\n*   +") else {
+            panic!("a blank column should not parse")
+        };
+        assert_eq!((error.line, error.column), (1, 3));
+        assert_eq!(error.expected, "a number down the column");
     }
 }
@@ -1,18 +1,23 @@
+use advent_of_code_common::parse::{ParseError, check_map};
 use aoc_runner_derive::{aoc, aoc_generator};
 use foldhash::{HashMap, HashMapExt};
 
 #[aoc_generator(day7)]
-fn parse(input: &str) -> (usize, Vec<Vec<usize>>) {
+fn parse(input: &str) -> Result<(usize, Vec<Vec<usize>>), ParseError> {
+    check_map(input, |c| ".S^".contains(c), "one of '.', 'S', '^'")?;
     let mut lines = input.lines();
 
-    let start = lines.next().unwrap().find('S').unwrap();
+    let first = lines.next().unwrap_or_default();
+    let start = first
+        .find('S')
+        .ok_or_else(|| ParseError::new(1, 1, "a row with the start 'S'", first))?;
     let splitters: Vec<Vec<usize>> = lines
         .skip(1)
         .step_by(2)
         .map(|l| l.match_indices('^').map(|(i, _)| i).collect())
         .collect();
 
-    (start, splitters)
+    Ok((start, splitters))
 }
 
 fn merge(beams: &mut HashMap<usize, u64>, splitters: &Vec<usize>) -> u64 {
@@ -88,11 +93,21 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse(INPUT)), 21);
+        assert_eq!(part1(&parse(INPUT).unwrap()), 21);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse(INPUT)), 40);
+        assert_eq!(part2(&parse(INPUT).unwrap()), 40);
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("...\n.S.").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 1));
+        assert_eq!(error.expected, "a row with the start 'S'");
+
+        let error = parse(".S.\n.v.").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
     }
 }
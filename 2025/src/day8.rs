@@ -1,6 +1,7 @@
 use foldhash::{HashMap, HashMapExt, HashSet, HashSetExt};
 use std::{cell::Cell, collections::BinaryHeap, ops::Mul, rc::Rc};
 
+use advent_of_code_common::parse::{ParseError, lines};
 use aoc_runner_derive::{aoc, aoc_generator};
 
 #[derive(Debug, Default)]
@@ -67,13 +68,18 @@ impl Mul for &JunctionBox {
 }
 
 #[aoc_generator(day8)]
-fn parse(input: &str) -> (Cell<BinaryHeap<Link>>, usize) {
+fn parse(input: &str) -> Result<(Cell<BinaryHeap<Link>>, usize), ParseError> {
     let mut links: Vec<Link> = vec![];
     let mut junction_boxes = vec![];
 
-    for line in input.lines() {
-        let v: Vec<_> = line.split(',').map(|v| v.parse::<u64>().unwrap()).collect();
-        let new = &Rc::new(JunctionBox::new(v[0], v[1], v[2]));
+    for mut line in lines(input) {
+        let x = line.number()?;
+        line.literal(",")?;
+        let y = line.number()?;
+        line.literal(",")?;
+        let z = line.number()?;
+        line.end()?;
+        let new = &Rc::new(JunctionBox::new(x, y, z));
 
         for jb in junction_boxes.iter() {
             links.push(Link::new(jb, new))
@@ -87,7 +93,7 @@ fn parse(input: &str) -> (Cell<BinaryHeap<Link>>, usize) {
         heap.push(jb)
     }
 
-    (Cell::new(heap), junction_boxes.len())
+    Ok((Cell::new(heap), junction_boxes.len()))
 }
 
 #[aoc(day8, part1)]
@@ -229,11 +235,23 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1_with_connections(&mut parse(INPUT).0.take(), 10), 40);
+        assert_eq!(
+            part1_with_connections(&mut parse(INPUT).unwrap().0.take(), 10),
+            40
+        );
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse(INPUT)), 25272);
+        assert_eq!(part2(&parse(INPUT).unwrap()), 25272);
+    }
+
+    #[test]
+    fn parse_error() {
+        let Err(error) = parse("162,817,812\n57,618") else {
+            panic!("a missing coordinate should not parse")
+        };
+        assert_eq!((error.line, error.column), (2, 7));
+        assert_eq!(error.expected, "','");
     }
 }
@@ -1,3 +1,4 @@
+use advent_of_code_common::parse::{ParseError, lines};
 use aoc_runner_derive::{aoc, aoc_generator};
 use foldhash::{HashMap, HashMapExt, HashSet, HashSetExt};
 
@@ -14,17 +15,19 @@ impl Tile {
 }
 
 #[aoc_generator(day9)]
-pub fn parse(input: &str) -> Vec<Tile> {
+pub fn parse(input: &str) -> Result<Vec<Tile>, ParseError> {
     let mut coords = Vec::new();
 
-    for line in input.lines() {
-        let (x, y) = line.split_once(',').unwrap();
-        let (x, y) = (x.parse().unwrap(), y.parse().unwrap());
+    for mut line in lines(input) {
+        let x = line.number()?;
+        line.literal(",")?;
+        let y = line.number()?;
+        line.end()?;
 
         coords.push(Tile::new(x, y));
     }
 
-    coords
+    Ok(coords)
 }
 
 #[aoc(day9, part1)]
@@ -162,11 +165,18 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse(INPUT)), 50);
+        assert_eq!(part1(&parse(INPUT).unwrap()), 50);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse(INPUT)), 24);
+        assert_eq!(part2(&parse(INPUT).unwrap()), 24);
+    }
+
+    #[test]
+    fn parse_error() {
+        let error = parse("7,1\n11;1").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(error.found(), ";");
     }
 }
@@ -1,3 +1,116 @@
-fn main() {
-    println!("Hello, world!");
+use std::process::ExitCode;
+
+use advent_of_code_2025::{SOLVERS, YEAR};
+use advent_of_code_common::{
+    inputs::InputStore,
+    solver::{Selection, Year, run_year},
+};
+
+const USAGE: &str = "Usage: advent-of-code-2025 [DAY [PART]] [--input <FILE|->] [--json]
+
+  DAY, PART      run a single day or a single part (default: every solver)
+  --input FILE   read the puzzle input from FILE, or from stdin with '-'
+                 (default: input/2025/day<DAY>.txt)
+  --json         print one JSON object per solver instead of text";
+
+struct Options {
+    selection: Selection,
+    input: Option<String>,
+    json: bool,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        selection: Selection::default(),
+        input: None,
+        json: false,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => options.json = true,
+            "--input" | "-i" => {
+                options.input = Some(args.next().ok_or("--input expects a path or '-'")?)
+            }
+            _ if options.selection.day.is_none() => {
+                options.selection.day =
+                    Some(arg.parse().map_err(|_| format!("invalid day '{arg}'"))?)
+            }
+            _ if options.selection.part.is_none() => {
+                options.selection.part =
+                    Some(arg.parse().map_err(|_| format!("invalid part '{arg}'"))?)
+            }
+            _ => return Err(format!("unexpected argument '{arg}'")),
+        }
+    }
+
+    if options.input.is_some() && options.selection.day.is_none() {
+        return Err("--input needs a DAY, every day has its own input".to_string());
+    }
+
+    Ok(options)
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    if !SOLVERS.iter().any(|s| options.selection.matches(s)) {
+        eprintln!("No solver registered for the requested day/part\n\n{USAGE}");
+        return ExitCode::from(2);
+    }
+
+    if !options.json {
+        println!("Advent of code {YEAR}\n");
+    }
+
+    let year = Year {
+        year: YEAR,
+        solvers: SOLVERS,
+    };
+    let summary = run_year(
+        &year,
+        options.selection,
+        options.input.as_deref(),
+        &InputStore::new("input").fetch_from_env(),
+        options.json,
+    );
+
+    if summary.failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> impl Iterator<Item = String> {
+        list.iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let options = parse_args(args(&["4", "2", "--input", "-", "--json"])).unwrap();
+        assert_eq!(
+            (options.selection.day, options.selection.part),
+            (Some(4), Some(2))
+        );
+        assert_eq!(options.input.as_deref(), Some("-"));
+        assert!(options.json);
+
+        assert!(parse_args(args(&["--input", "day4.txt"])).is_err());
+        assert!(parse_args(args(&["four"])).is_err());
+        assert!(parse_args(args(&["4", "1", "1"])).is_err());
+    }
 }
@@ -0,0 +1,241 @@
+use std::{collections::BTreeMap, fmt::Write as _, fs, io};
+
+use crate::{
+    inputs::InputStore,
+    output::{self, Tally, json_string},
+    solver::{Outcome, Selection, Solver, Year, solve_year},
+};
+
+/// The accepted answers of one year, stored as `<dir>/<year>.toml`:
+///
+/// ```toml
+/// [day7]
+/// part1 = 3176
+/// part2 = "abc"
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Answers(BTreeMap<(u8, u8), String>);
+
+impl Answers {
+    /// Reads the answers of `year` from `dir`, a missing file meaning no answer is known yet
+    pub fn load(dir: &str, year: u32) -> Result<Self, String> {
+        let path = format!("{dir}/{year}.toml");
+        match fs::read_to_string(&path) {
+            Ok(text) => Answers::parse(&text).map_err(|e| format!("{path}: {e}")),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => Err(format!("{path}: {e}")),
+        }
+    }
+
+    pub fn save(&self, dir: &str, year: u32) -> Result<(), String> {
+        let path = format!("{dir}/{year}.toml");
+        fs::create_dir_all(dir)
+            .and_then(|_| fs::write(&path, self.to_toml(year)))
+            .map_err(|e| format!("{path}: {e}"))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let table: toml::Table = text.parse().map_err(|e: toml::de::Error| e.to_string())?;
+        let mut answers = Answers::default();
+
+        for (day_key, parts) in &table {
+            let day = key_number(day_key, "day")?;
+            let parts = parts
+                .as_table()
+                .ok_or_else(|| format!("[{day_key}] should be a table of parts"))?;
+
+            for (part_key, answer) in parts {
+                let part = key_number(part_key, "part")?;
+                let answer = match answer {
+                    toml::Value::String(answer) => answer.clone(),
+                    toml::Value::Integer(answer) => answer.to_string(),
+                    other => {
+                        return Err(format!(
+                            "{day_key}.{part_key}: expected a string or an integer, found {}",
+                            other.type_str()
+                        ));
+                    }
+                };
+                answers.insert(day, part, answer);
+            }
+        }
+
+        Ok(answers)
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&str> {
+        self.0.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, day: u8, part: u8, answer: String) {
+        self.0.insert((day, part), answer);
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Days in order, numeric answers written as integers and everything else as strings
+    pub fn to_toml(&self, year: u32) -> String {
+        let mut text = format!("# Accepted answers for {year}, checked by `aoc verify {year}`\n");
+        let mut current = None;
+
+        for (&(day, part), answer) in &self.0 {
+            if current != Some(day) {
+                write!(text, "\n[day{day}]\n").unwrap();
+                current = Some(day);
+            }
+
+            match answer.parse::<i64>() {
+                Ok(n) if n.to_string() == *answer => writeln!(text, "part{part} = {answer}"),
+                _ => writeln!(text, "part{part} = {}", json_string(answer)),
+            }
+            .unwrap();
+        }
+
+        text
+    }
+
+    pub fn check(&self, solver: &Solver, outcome: &Outcome) -> Verdict {
+        let Outcome::Solved { answer, .. } = outcome else {
+            return Verdict::Failed;
+        };
+
+        match self.get(solver.day, solver.part) {
+            Some(expected) if expected == answer => Verdict::Pass,
+            Some(expected) => Verdict::Wrong {
+                expected: expected.to_string(),
+            },
+            None => Verdict::Missing,
+        }
+    }
+}
+
+fn key_number(key: &str, prefix: &str) -> Result<u8, String> {
+    key.strip_prefix(prefix)
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(|| format!("expected a key like '{prefix}1', found '{key}'"))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Wrong {
+        expected: String,
+    },
+    Missing,
+    /// Was missing, the answer just found is now stored
+    Recorded,
+    Failed,
+}
+
+/// Runs the selected solvers of `year` and compares them with `answers`,
+/// storing the answers that were missing when `record` is set
+pub fn verify_year(
+    year: &Year,
+    selection: Selection,
+    store: &InputStore,
+    answers: &mut Answers,
+    record: bool,
+    json: bool,
+) -> Tally {
+    let mut tally = Tally::new(year.year);
+
+    solve_year(year, selection, None, store, |solver, outcome| {
+        let mut verdict = answers.check(solver, &outcome);
+        if let (Verdict::Missing, true, Outcome::Solved { answer, .. }) =
+            (&verdict, record, &outcome)
+        {
+            answers.insert(solver.day, solver.part, answer.clone());
+            verdict = Verdict::Recorded;
+        }
+
+        output::report_verdict(year.year, solver, &outcome, &verdict, json);
+        tally.add(&outcome, &verdict);
+    });
+
+    tally
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, time::Duration};
+
+    use aoc_runner::ArcStr;
+
+    use super::*;
+
+    const TOML: &str = "# Accepted answers for 2015, checked by `aoc verify 2015`
+
+[day7]
+part1 = 3176
+part2 = \"abc\"
+
+[day12]
+part1 = -3
+";
+
+    #[test]
+    fn test_parse() {
+        let answers = Answers::parse(TOML).unwrap();
+        assert_eq!(answers.len(), 3);
+        assert_eq!(answers.get(7, 1), Some("3176"));
+        assert_eq!(answers.get(7, 2), Some("abc"));
+        assert_eq!(answers.get(12, 1), Some("-3"));
+        assert_eq!(answers.get(12, 2), None);
+        assert_eq!(answers.to_toml(2015), TOML);
+
+        assert!(Answers::parse("[day7]\npart1 = 1.5").is_err());
+        assert!(Answers::parse("[seven]\npart1 = 1").is_err());
+        assert!(Answers::parse("[day7]\nfirst = 1").is_err());
+        assert!(Answers::parse("day7 = 1").is_err());
+    }
+
+    #[test]
+    fn test_numeric_strings() {
+        let mut answers = Answers::default();
+        answers.insert(1, 1, "007".to_string());
+        answers.insert(1, 2, "7".to_string());
+        assert_eq!(Answers::parse(&answers.to_toml(2025)).unwrap(), answers);
+        assert!(
+            answers
+                .to_toml(2025)
+                .contains("part1 = \"007\"\npart2 = 7\n")
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        fn build(_: ArcStr) -> Result<Box<dyn aoc_runner::Runner>, Box<dyn Error>> {
+            unreachable!()
+        }
+
+        let answers = Answers::parse(TOML).unwrap();
+        let solved = |answer: &str| Outcome::Solved {
+            answer: answer.to_string(),
+            parse: Duration::ZERO,
+            solve: Duration::ZERO,
+        };
+
+        let solver = Solver::new(7, 1, build);
+        assert_eq!(answers.check(&solver, &solved("3176")), Verdict::Pass);
+        assert_eq!(
+            answers.check(&solver, &solved("3177")),
+            Verdict::Wrong {
+                expected: "3176".to_string()
+            }
+        );
+        assert_eq!(
+            answers.check(&solver, &Outcome::Failed("boom".to_string())),
+            Verdict::Failed
+        );
+        assert_eq!(
+            answers.check(&Solver::new(8, 1, build), &solved("1")),
+            Verdict::Missing
+        );
+    }
+}
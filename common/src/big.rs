@@ -0,0 +1,217 @@
+//! Unsigned integers of any size, for counts that outgrow `u128`. Only what counting needs:
+//! addition, multiplication by machine words, comparison and decimal output.
+
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display},
+    iter::Sum,
+    ops::{Add, AddAssign, Mul, MulAssign},
+};
+
+/// Little endian 32 bit limbs, without leading zero limbs, so that zero has none
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint(Vec<u32>);
+
+impl BigUint {
+    pub fn zero() -> Self {
+        BigUint(vec![])
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.0.len() > 4 {
+            return None;
+        }
+        Some(
+            self.0
+                .iter()
+                .rev()
+                .fold(0, |acc, &limb| acc << 32 | u128::from(limb)),
+        )
+    }
+
+    /// The number of bits needed to write the number, 0 for zero
+    pub fn bits(&self) -> u64 {
+        match self.0.last() {
+            Some(top) => self.0.len() as u64 * 32 - u64::from(top.leading_zeros()),
+            None => 0,
+        }
+    }
+
+    /// Divides in place by `divisor`, returning the remainder
+    fn div_rem_small(&mut self, divisor: u32) -> u32 {
+        let mut remainder = 0u64;
+        for limb in self.0.iter_mut().rev() {
+            let current = remainder << 32 | u64::from(*limb);
+            *limb = (current / u64::from(divisor)) as u32;
+            remainder = current % u64::from(divisor);
+        }
+        self.trim();
+        remainder as u32
+    }
+
+    fn trim(&mut self) {
+        while self.0.last() == Some(&0) {
+            self.0.pop();
+        }
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(mut value: u128) -> Self {
+        let mut limbs = vec![];
+        while value != 0 {
+            limbs.push(value as u32);
+            value >>= 32;
+        }
+        BigUint(limbs)
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        BigUint::from(u128::from(value))
+    }
+}
+
+impl From<usize> for BigUint {
+    fn from(value: usize) -> Self {
+        BigUint::from(value as u128)
+    }
+}
+
+impl AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, other: &BigUint) {
+        if self.0.len() < other.0.len() {
+            self.0.resize(other.0.len(), 0);
+        }
+
+        let mut carry = 0u64;
+        for (i, limb) in self.0.iter_mut().enumerate() {
+            let sum = u64::from(*limb) + u64::from(other.0.get(i).copied().unwrap_or(0)) + carry;
+            *limb = sum as u32;
+            carry = sum >> 32;
+            if carry == 0 && i >= other.0.len() {
+                break;
+            }
+        }
+        if carry != 0 {
+            self.0.push(carry as u32);
+        }
+    }
+}
+
+impl Add<&BigUint> for BigUint {
+    type Output = BigUint;
+
+    fn add(mut self, other: &BigUint) -> BigUint {
+        self += other;
+        self
+    }
+}
+
+impl MulAssign<u32> for BigUint {
+    fn mul_assign(&mut self, factor: u32) {
+        let mut carry = 0u64;
+        for limb in &mut self.0 {
+            let product = u64::from(*limb) * u64::from(factor) + carry;
+            *limb = product as u32;
+            carry = product >> 32;
+        }
+        if carry != 0 {
+            self.0.push(carry as u32);
+        }
+        self.trim();
+    }
+}
+
+impl Mul<u32> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, factor: u32) -> BigUint {
+        let mut product = self.clone();
+        product *= factor;
+        product
+    }
+}
+
+impl<'a> Sum<&'a BigUint> for BigUint {
+    fn sum<I: Iterator<Item = &'a BigUint>>(iter: I) -> Self {
+        iter.fold(BigUint::zero(), |acc, n| acc + n)
+    }
+}
+
+impl Sum for BigUint {
+    fn sum<I: Iterator<Item = BigUint>>(iter: I) -> Self {
+        iter.fold(BigUint::zero(), |acc, n| acc + &n)
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0
+            .len()
+            .cmp(&other.0.len())
+            .then_with(|| self.0.iter().rev().cmp(other.0.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Nine decimal digits at a time, lowest first
+        let mut rest = self.clone();
+        let mut chunks = vec![];
+        while !rest.is_zero() {
+            chunks.push(rest.div_rem_small(1_000_000_000));
+        }
+
+        let mut digits = match chunks.pop() {
+            Some(top) => top.to_string(),
+            None => "0".to_string(),
+        };
+        for chunk in chunks.iter().rev() {
+            digits += &format!("{chunk:09}");
+        }
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let mut n = BigUint::from(u64::MAX);
+        n += &BigUint::from(1u64);
+        assert_eq!(n.to_u128(), Some(1 << 64));
+        assert_eq!(n.bits(), 65);
+
+        let mut power = BigUint::from(1u64);
+        for _ in 0..130 {
+            power *= 2;
+        }
+        assert_eq!(
+            power.to_string(),
+            "1361129467683753853853498429727072845824"
+        );
+        assert_eq!(power.to_u128(), None);
+        assert!(power > n && n > BigUint::zero());
+
+        assert_eq!(BigUint::zero().to_string(), "0");
+        assert_eq!(format!("{:>5}", BigUint::from(42u64)), "   42");
+        assert_eq!(
+            [1u64, 2, 3].map(BigUint::from).into_iter().sum::<BigUint>(),
+            BigUint::from(6u64)
+        );
+    }
+}
@@ -0,0 +1,333 @@
+use std::{
+    error::Error,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Downloads a puzzle input that isn't cached yet
+pub trait Fetcher {
+    fn fetch(&self, year: u32, day: u8) -> Result<String, String>;
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    /// No cached file and no fetcher to download it with
+    NotCached {
+        year: u32,
+        day: u8,
+        path: PathBuf,
+    },
+    Fetch {
+        year: u32,
+        day: u8,
+        reason: String,
+    },
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::NotCached { year, day, path } => write!(
+                f,
+                "input of {year} day {day} is not cached at {}",
+                path.display()
+            ),
+            InputError::Fetch { year, day, reason } => {
+                write!(f, "fetching the input of {year} day {day} failed: {reason}")
+            }
+            InputError::Io { path, error } => write!(f, "{}: {error}", path.display()),
+        }
+    }
+}
+
+impl Error for InputError {}
+
+/// Puzzle inputs cached as `<dir>/<year>/day<N>.txt`, fetched on a miss when a fetcher is set
+pub struct InputStore {
+    dir: PathBuf,
+    fetcher: Option<Box<dyn Fetcher>>,
+}
+
+impl InputStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        InputStore {
+            dir: dir.into(),
+            fetcher: None,
+        }
+    }
+
+    /// Downloads missing inputs with the `$AOC_SESSION` cookie, when set and built with `http`
+    pub fn fetch_from_env(self) -> Self {
+        #[cfg(feature = "http")]
+        if let Some(fetcher) = HttpFetcher::from_env() {
+            return self.with_fetcher(fetcher);
+        }
+
+        self
+    }
+
+    pub fn with_fetcher(mut self, fetcher: impl Fetcher + 'static) -> Self {
+        self.fetcher = Some(Box::new(fetcher));
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn path(&self, year: u32, day: u8) -> PathBuf {
+        self.dir
+            .join(year.to_string())
+            .join(format!("day{day}.txt"))
+    }
+
+    pub fn load(&self, year: u32, day: u8) -> Result<String, InputError> {
+        let path = self.path(year, day);
+
+        match fs::read_to_string(&path) {
+            Ok(input) => Ok(input),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                let Some(fetcher) = &self.fetcher else {
+                    return Err(InputError::NotCached { year, day, path });
+                };
+
+                let input = fetcher
+                    .fetch(year, day)
+                    .map_err(|reason| InputError::Fetch { year, day, reason })?;
+
+                path.parent()
+                    .map_or(Ok(()), fs::create_dir_all)
+                    .and_then(|_| fs::write(&path, &input))
+                    .map_err(|error| InputError::Io { path, error })?;
+
+                Ok(input)
+            }
+            Err(error) => Err(InputError::Io { path, error }),
+        }
+    }
+}
+
+/// The input of a puzzle binary: the file at `path` when given, otherwise `year` day `day` from
+/// the `input` directory of the crate at `manifest_dir`, fetched on a miss like the runner does
+pub fn load_for(
+    manifest_dir: &str,
+    path: Option<&str>,
+    year: u32,
+    day: u8,
+) -> Result<String, String> {
+    match path {
+        Some(path) => fs::read_to_string(path).map_err(|e| format!("{path}: {e}")),
+        None => InputStore::new(Path::new(manifest_dir).join("input"))
+            .fetch_from_env()
+            .load(year, day)
+            .map_err(|e| e.to_string()),
+    }
+}
+
+/// Downloads inputs from the Advent of Code website with a session cookie
+#[cfg(feature = "http")]
+pub struct HttpFetcher {
+    base: String,
+    session: String,
+}
+
+#[cfg(feature = "http")]
+impl HttpFetcher {
+    pub const BASE: &str = "https://adventofcode.com";
+
+    pub fn new(session: impl Into<String>) -> Self {
+        HttpFetcher {
+            base: HttpFetcher::BASE.to_string(),
+            session: session.into(),
+        }
+    }
+
+    /// Reads the session cookie from `$AOC_SESSION`
+    pub fn from_env() -> Option<Self> {
+        std::env::var("AOC_SESSION")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| HttpFetcher::new(s.trim()))
+    }
+
+    /// Talks to another server than the website, such as a local stand-in
+    pub fn with_base(mut self, base: impl Into<String>) -> Self {
+        self.base = base.into();
+        self
+    }
+}
+
+#[cfg(feature = "http")]
+impl Fetcher for HttpFetcher {
+    fn fetch(&self, year: u32, day: u8) -> Result<String, String> {
+        let url = format!("{}/{year}/day/{day}/input", self.base.trim_end_matches('/'));
+
+        ureq::get(&url)
+            .header("Cookie", &format!("session={}", self.session))
+            .header("User-Agent", "advent-of-code runner")
+            .call()
+            .and_then(|mut response| response.body_mut().read_to_string())
+            .map_err(|e| format!("{url}: {e}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+
+    struct Fake {
+        calls: Rc<Cell<usize>>,
+        answer: Result<&'static str, &'static str>,
+    }
+
+    impl Fetcher for Fake {
+        fn fetch(&self, _: u32, _: u8) -> Result<String, String> {
+            self.calls.set(self.calls.get() + 1);
+            self.answer.map(str::to_string).map_err(str::to_string)
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-inputs-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_cache() {
+        let dir = temp_dir("cache");
+        let calls = Rc::new(Cell::new(0));
+        let store = InputStore::new(&dir).with_fetcher(Fake {
+            calls: calls.clone(),
+            answer: Ok("1\n2\n"),
+        });
+
+        assert_eq!(store.path(2015, 7), dir.join("2015").join("day7.txt"));
+        assert_eq!(store.load(2015, 7).unwrap(), "1\n2\n");
+        assert_eq!(store.load(2015, 7).unwrap(), "1\n2\n");
+        assert_eq!(calls.get(), 1);
+        assert!(matches!(
+            InputStore::new(&dir).load(2015, 7).as_deref(),
+            Ok("1\n2\n")
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_errors() {
+        let dir = temp_dir("errors");
+
+        let error = InputStore::new(&dir).load(2024, 3).unwrap_err();
+        assert!(matches!(
+            error,
+            InputError::NotCached {
+                year: 2024,
+                day: 3,
+                ..
+            }
+        ));
+        assert!(
+            error
+                .to_string()
+                .starts_with("input of 2024 day 3 is not cached at ")
+        );
+
+        let store = InputStore::new(&dir).with_fetcher(Fake {
+            calls: Default::default(),
+            answer: Err("HTTP 404"),
+        });
+        let error = store.load(2024, 3).unwrap_err();
+        assert!(matches!(error, InputError::Fetch { .. }));
+        assert_eq!(
+            error.to_string(),
+            "fetching the input of 2024 day 3 failed: HTTP 404"
+        );
+        assert!(!store.path(2024, 3).exists());
+    }
+
+    #[test]
+    fn test_load_for() {
+        let dir = temp_dir("load-for");
+        let store = InputStore::new(dir.join("input"));
+        fs::create_dir_all(store.path(2015, 7).parent().unwrap()).unwrap();
+        fs::write(store.path(2015, 7), "cached").unwrap();
+        let other = dir.join("other.txt");
+        fs::write(&other, "other").unwrap();
+
+        let manifest_dir = dir.to_str().unwrap();
+        assert_eq!(
+            load_for(manifest_dir, None, 2015, 7).as_deref(),
+            Ok("cached")
+        );
+        let path = other.to_str().unwrap();
+        assert_eq!(
+            load_for(manifest_dir, Some(path), 2015, 7).as_deref(),
+            Ok("other")
+        );
+
+        let missing = dir.join("missing.txt");
+        let missing = missing.to_str().unwrap();
+        let error = load_for(manifest_dir, Some(missing), 2015, 7).unwrap_err();
+        assert!(error.starts_with(&format!("{missing}: ")), "{error}");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_http_fetcher() {
+        use std::{
+            io::{BufRead, BufReader, Write},
+            net::TcpListener,
+            thread,
+        };
+
+        // A stand-in for the website, answering two requests
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            for _ in 0..2 {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut request = vec![];
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    request.push(line.trim().to_string());
+                }
+
+                let authorized = request
+                    .iter()
+                    .any(|h| h.eq_ignore_ascii_case("cookie: session=secret"));
+                let response = match (request[0].as_str(), authorized) {
+                    ("GET /2015/day/7/input HTTP/1.1", true) => {
+                        "HTTP/1.1 200 OK\r\nContent-Length: 4\r\nConnection: close\r\n\r\n123\n"
+                    }
+                    _ => {
+                        "HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    }
+                };
+                reader.get_mut().write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        let fetcher = HttpFetcher::new("secret").with_base(&base);
+        assert_eq!(fetcher.fetch(2015, 7).as_deref(), Ok("123\n"));
+
+        let fetcher = HttpFetcher::new("expired").with_base(&base);
+        let error = fetcher.fetch(2015, 7).unwrap_err();
+        assert!(error.contains("400"), "{error}");
+
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,6 @@
+pub mod answers;
+pub mod big;
+pub mod inputs;
+pub mod output;
+pub mod parse;
+pub mod solver;
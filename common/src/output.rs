@@ -0,0 +1,312 @@
+use std::{fmt::Write as _, time::Duration};
+
+use crate::{
+    answers::Verdict,
+    solver::{Outcome, Solver},
+};
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Summary {
+    pub year: u32,
+    pub solved: usize,
+    pub failed: usize,
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+impl Summary {
+    pub fn new(year: u32) -> Self {
+        Summary {
+            year,
+            ..Default::default()
+        }
+    }
+
+    pub fn add(&mut self, outcome: &Outcome) {
+        match outcome {
+            Outcome::Solved { parse, solve, .. } => {
+                self.solved += 1;
+                self.parse += *parse;
+                self.solve += *solve;
+            }
+            Outcome::Failed(_) => self.failed += 1,
+        }
+    }
+}
+
+/// Verification counts for one year, recorded answers counting as missing
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Tally {
+    pub year: u32,
+    pub passed: usize,
+    pub wrong: usize,
+    pub missing: usize,
+    pub failed: usize,
+    pub time: Duration,
+}
+
+impl Tally {
+    pub fn new(year: u32) -> Self {
+        Tally {
+            year,
+            ..Default::default()
+        }
+    }
+
+    pub fn add(&mut self, outcome: &Outcome, verdict: &Verdict) {
+        if let Outcome::Solved { parse, solve, .. } = outcome {
+            self.time += *parse + *solve;
+        }
+
+        match verdict {
+            Verdict::Pass => self.passed += 1,
+            Verdict::Wrong { .. } => self.wrong += 1,
+            Verdict::Missing | Verdict::Recorded => self.missing += 1,
+            Verdict::Failed => self.failed += 1,
+        }
+    }
+}
+
+pub fn json_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(escaped, "\\u{:04x}", c as u32).unwrap(),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+pub fn report(year: u32, solver: &Solver, outcome: &Outcome, json: bool) {
+    let (day, part) = (solver.day, solver.part);
+
+    match (outcome, json) {
+        (
+            Outcome::Solved {
+                answer,
+                parse,
+                solve,
+            },
+            false,
+        ) => println!(
+            "Day {day} - Part {part}: {answer}\n\tgenerator: {parse:?},\n\trunner: {solve:?}\n"
+        ),
+        (Outcome::Failed(error), false) => eprintln!("Day {day} - Part {part}: FAILED {error}\n"),
+        (
+            Outcome::Solved {
+                answer,
+                parse,
+                solve,
+            },
+            true,
+        ) => println!(
+            r#"{{"year":{year},"day":{day},"part":{part},"answer":{},"parse_ns":{},"solve_ns":{}}}"#,
+            json_string(answer),
+            parse.as_nanos(),
+            solve.as_nanos()
+        ),
+        (Outcome::Failed(error), true) => println!(
+            r#"{{"year":{year},"day":{day},"part":{part},"error":{}}}"#,
+            json_string(error)
+        ),
+    }
+}
+
+pub fn report_verdict(
+    year: u32,
+    solver: &Solver,
+    outcome: &Outcome,
+    verdict: &Verdict,
+    json: bool,
+) {
+    let (day, part) = (solver.day, solver.part);
+
+    let (answer, parse, solve) = match outcome {
+        Outcome::Solved {
+            answer,
+            parse,
+            solve,
+        } => (answer, parse, solve),
+        Outcome::Failed(error) if json => {
+            return println!(
+                r#"{{"year":{year},"day":{day},"part":{part},"status":"failed","error":{}}}"#,
+                json_string(error)
+            );
+        }
+        Outcome::Failed(error) => return eprintln!("Day {day} - Part {part}: FAILED {error}"),
+    };
+
+    let (status, expected) = match verdict {
+        Verdict::Pass => ("pass", None),
+        Verdict::Wrong { expected } => ("wrong", Some(expected)),
+        Verdict::Missing => ("missing", None),
+        Verdict::Recorded => ("recorded", None),
+        Verdict::Failed => ("failed", None),
+    };
+
+    if json {
+        let expected = expected.map_or(String::new(), |e| {
+            format!(r#","expected":{}"#, json_string(e))
+        });
+        println!(
+            r#"{{"year":{year},"day":{day},"part":{part},"status":"{status}","answer":{}{expected},"parse_ns":{},"solve_ns":{}}}"#,
+            json_string(answer),
+            parse.as_nanos(),
+            solve.as_nanos()
+        );
+    } else {
+        let expected = expected.map_or(String::new(), |e| format!(", expected {e}"));
+        println!(
+            "Day {day} - Part {part}: {} {answer}{expected} (generator: {parse:.1?}, runner: {solve:.1?})",
+            status.to_uppercase()
+        );
+    }
+}
+
+pub fn summary_table(summaries: &[Summary]) -> String {
+    let mut table = format!(
+        "{:<6}{:>8}{:>8}{:>14}{:>14}\n",
+        "Year", "Solved", "Failed", "Generator", "Runner"
+    );
+    let mut total = Summary::default();
+
+    for s in summaries {
+        writeln!(
+            table,
+            "{:<6}{:>8}{:>8}{:>14}{:>14}",
+            s.year,
+            s.solved,
+            s.failed,
+            format!("{:.1?}", s.parse),
+            format!("{:.1?}", s.solve)
+        )
+        .unwrap();
+
+        total.solved += s.solved;
+        total.failed += s.failed;
+        total.parse += s.parse;
+        total.solve += s.solve;
+    }
+
+    write!(
+        table,
+        "{:<6}{:>8}{:>8}{:>14}{:>14}",
+        "Total",
+        total.solved,
+        total.failed,
+        format!("{:.1?}", total.parse),
+        format!("{:.1?}", total.solve)
+    )
+    .unwrap();
+
+    table
+}
+
+pub fn verify_table(tallies: &[Tally]) -> String {
+    let mut table = format!(
+        "{:<6}{:>8}{:>8}{:>8}{:>8}{:>14}\n",
+        "Year", "Passed", "Wrong", "Missing", "Failed", "Time"
+    );
+    let mut total = Tally::default();
+
+    for t in tallies {
+        writeln!(
+            table,
+            "{:<6}{:>8}{:>8}{:>8}{:>8}{:>14}",
+            t.year,
+            t.passed,
+            t.wrong,
+            t.missing,
+            t.failed,
+            format!("{:.1?}", t.time)
+        )
+        .unwrap();
+
+        total.passed += t.passed;
+        total.wrong += t.wrong;
+        total.missing += t.missing;
+        total.failed += t.failed;
+        total.time += t.time;
+    }
+
+    write!(
+        table,
+        "{:<6}{:>8}{:>8}{:>8}{:>8}{:>14}",
+        "Total",
+        total.passed,
+        total.wrong,
+        total.missing,
+        total.failed,
+        format!("{:.1?}", total.time)
+    )
+    .unwrap();
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("abc"), r#""abc""#);
+        assert_eq!(json_string("a\"b\\c\nd"), r#""a\"b\\c\nd""#);
+        assert_eq!(json_string("\u{1}"), r#""\u0001""#);
+    }
+
+    #[test]
+    fn test_summary_table() {
+        let mut summary = Summary::new(2015);
+        summary.add(&Outcome::Solved {
+            answer: "1".to_string(),
+            parse: Duration::from_millis(2),
+            solve: Duration::from_millis(3),
+        });
+        summary.add(&Outcome::Failed("boom".to_string()));
+
+        let table = summary_table(&[summary]);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[1],
+            "2015         1       1         2.0ms         3.0ms"
+        );
+        assert!(lines[2].starts_with("Total        1       1"));
+    }
+
+    #[test]
+    fn test_verify_table() {
+        let solved = Outcome::Solved {
+            answer: "1".to_string(),
+            parse: Duration::from_millis(2),
+            solve: Duration::from_millis(3),
+        };
+        let mut tally = Tally::new(2025);
+        tally.add(&solved, &Verdict::Pass);
+        tally.add(
+            &solved,
+            &Verdict::Wrong {
+                expected: "2".to_string(),
+            },
+        );
+        tally.add(&solved, &Verdict::Recorded);
+        tally.add(&Outcome::Failed("boom".to_string()), &Verdict::Failed);
+
+        let table = verify_table(&[tally]);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[1],
+            "2025         1       1       1       1        15.0ms"
+        );
+        assert!(lines[2].starts_with("Total        1       1       1       1"));
+    }
+}
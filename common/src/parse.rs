@@ -0,0 +1,324 @@
+use std::{error::Error, fmt, str::FromStr};
+
+/// A generator failure, pointing at the offending line and column (both 1-based)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub text: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, expected: impl Into<String>, text: &str) -> Self {
+        ParseError {
+            line,
+            column,
+            expected: expected.into(),
+            text: text.to_string(),
+        }
+    }
+
+    /// The token under the column, or an empty string at the end of the line
+    pub fn found(&self) -> &str {
+        let start = self
+            .text
+            .char_indices()
+            .nth(self.column - 1)
+            .map_or(self.text.len(), |(i, _)| i);
+        let rest = &self.text[start..];
+        let word = |c: char| c.is_alphanumeric() || c == '_';
+        let end = match rest.chars().next() {
+            Some(c) if word(c) => rest.find(|c| !word(c)).unwrap_or(rest.len()),
+            Some(c) => c.len_utf8(),
+            None => 0,
+        };
+
+        &rest[..end]
+    }
+
+    /// Moves the error down by `lines`, for parsers that only see a chunk of the input
+    pub fn shifted(mut self, lines: usize) -> Self {
+        self.line += lines;
+        self
+    }
+
+    /// The offending line with a caret under the column
+    pub fn snippet(&self) -> String {
+        let gutter = self.line.to_string().len();
+        format!(
+            "{:>gutter$} | {}\n{:>gutter$} | {:>column$} expected {}",
+            self.line,
+            self.text,
+            "",
+            "^",
+            self.expected,
+            column = self.column
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, found ",
+            self.line, self.column, self.expected
+        )?;
+        match self.found() {
+            "" => write!(f, "end of line"),
+            found => write!(f, "'{found}'"),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Reads a single input line token by token, keeping track of the column for errors
+#[derive(Debug, Clone)]
+pub struct Cursor<'a> {
+    line: usize,
+    text: &'a str,
+    offset: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(line: usize, text: &'a str) -> Self {
+        Cursor {
+            line,
+            text,
+            offset: 0,
+        }
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.text[..self.offset].chars().count() + 1
+    }
+
+    pub fn rest(&self) -> &'a str {
+        &self.text[self.offset..]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rest().is_empty()
+    }
+
+    pub fn error(&self, expected: impl Into<String>) -> ParseError {
+        ParseError::new(self.line, self.column(), expected, self.text)
+    }
+
+    pub fn skip_whitespace(&mut self) {
+        self.take_while(char::is_whitespace);
+    }
+
+    pub fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let rest = self.rest();
+        let length = rest.find(|c| !predicate(c)).unwrap_or(rest.len());
+        self.offset += length;
+        &rest[..length]
+    }
+
+    /// Consumes `literal` if the line continues with it
+    pub fn eat(&mut self, literal: &str) -> bool {
+        if self.rest().starts_with(literal) {
+            self.offset += literal.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consumes `literal`, or points the error at the first of its words that doesn't match
+    pub fn literal(&mut self, literal: &str) -> Result<(), ParseError> {
+        if self.eat(literal) {
+            return Ok(());
+        }
+
+        let matched = (1..literal.len())
+            .filter(|&i| literal.is_char_boundary(i))
+            .filter(|&i| {
+                let (before, after) = literal.split_at(i);
+                before.ends_with(char::is_whitespace) != after.starts_with(char::is_whitespace)
+            })
+            .filter(|&i| self.rest().starts_with(&literal[..i]))
+            .max()
+            .unwrap_or(0);
+
+        let mut at = self.clone();
+        at.offset += matched;
+        Err(match literal[matched..].trim() {
+            "" => at.error("whitespace"),
+            missing => at.error(format!("'{missing}'")),
+        })
+    }
+
+    /// An optionally signed run of digits, converted to `T`
+    pub fn number<T: FromStr>(&mut self) -> Result<T, ParseError> {
+        let start = self.offset;
+        let sign = usize::from(self.rest().starts_with(['-', '+']));
+        self.offset += sign;
+
+        if self.take_while(|c| c.is_ascii_digit()).is_empty() {
+            self.offset = start;
+            return Err(self.error("number"));
+        }
+
+        self.text[start..self.offset].parse().map_err(|_| {
+            let error = ParseError::new(
+                self.line,
+                self.text[..start].chars().count() + 1,
+                format!("number fitting {}", std::any::type_name::<T>()),
+                self.text,
+            );
+            self.offset = start;
+            error
+        })
+    }
+
+    /// A single decimal digit
+    pub fn digit(&mut self) -> Result<u32, ParseError> {
+        match self.rest().chars().next().and_then(|c| c.to_digit(10)) {
+            Some(digit) => {
+                self.offset += 1;
+                Ok(digit)
+            }
+            None => Err(self.error("digit")),
+        }
+    }
+
+    /// A run of letters, digits and underscores
+    pub fn word(&mut self) -> Result<&'a str, ParseError> {
+        match self.take_while(|c| c.is_alphanumeric() || c == '_') {
+            "" => Err(self.error("word")),
+            word => Ok(word),
+        }
+    }
+
+    /// Consumes whichever of the `options` the line continues with
+    pub fn one_of<T: Copy>(&mut self, options: &[(&str, T)]) -> Result<T, ParseError> {
+        for &(literal, value) in options {
+            if self.eat(literal) {
+                return Ok(value);
+            }
+        }
+
+        let names: Vec<String> = options.iter().map(|(l, _)| format!("'{l}'")).collect();
+        Err(self.error(format!("one of {}", names.join(", "))))
+    }
+
+    pub fn end(&mut self) -> Result<(), ParseError> {
+        self.skip_whitespace();
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error("end of line"))
+        }
+    }
+}
+
+/// One cursor per line, numbered from 1 and already past any indentation
+pub fn lines(input: &str) -> impl Iterator<Item = Cursor<'_>> {
+    input.lines().enumerate().map(|(i, text)| {
+        let mut cursor = Cursor::new(i + 1, text);
+        cursor.skip_whitespace();
+        cursor
+    })
+}
+
+/// Checks a character map: rows of the same width, made only of cells accepted by `cell`
+pub fn check_map(
+    input: &str,
+    cell: impl Fn(char) -> bool,
+    expected: &str,
+) -> Result<(), ParseError> {
+    let mut width = None;
+
+    for (i, text) in input.lines().enumerate() {
+        let mut row = Cursor::new(i + 1, text);
+        let cells = row.take_while(&cell).chars().count();
+        if !row.is_empty() {
+            return Err(row.error(expected));
+        }
+
+        match *width.get_or_insert(cells) {
+            width if width == cells => {}
+            width if width < cells => {
+                return Err(ParseError::new(i + 1, width + 1, "end of row", text));
+            }
+            _ => return Err(row.error(expected)),
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor() {
+        let mut cursor = lines("  turn on 0,-12 through x").next().unwrap();
+        assert_eq!(
+            cursor.clone().literal("turn off"),
+            Err(ParseError::new(1, 8, "'off'", "  turn on 0,-12 through x"))
+        );
+        cursor.literal("turn ").unwrap();
+        assert_eq!(cursor.one_of(&[("off", false), ("on", true)]), Ok(true));
+        cursor.skip_whitespace();
+        assert_eq!(cursor.number::<u32>(), Ok(0));
+        cursor.literal(",").unwrap();
+        assert!(cursor.number::<u32>().is_err());
+        assert_eq!(cursor.number::<i32>(), Ok(-12));
+        assert_eq!(cursor.digit(), Err(cursor.error("digit")));
+        assert_eq!(cursor.word(), Err(cursor.error("word")));
+        cursor.skip_whitespace();
+        assert_eq!(cursor.word(), Ok("through"));
+
+        let error = cursor.end().unwrap_err();
+        assert_eq!((error.line, error.column), (1, 25));
+        assert_eq!(error.found(), "x");
+    }
+
+    #[test]
+    fn test_error() {
+        let error = ParseError::new(12, 13, "'through'", "turn on 0,0 thru 9,9");
+        assert_eq!(error.found(), "thru");
+        assert_eq!(
+            error.to_string(),
+            "line 12, column 13: expected 'through', found 'thru'"
+        );
+        assert_eq!(
+            error.snippet(),
+            "12 | turn on 0,0 thru 9,9\n   |             ^ expected 'through'"
+        );
+
+        let error = ParseError::new(1, 4, "number", "a: ").shifted(2);
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 4: expected number, found end of line"
+        );
+    }
+
+    #[test]
+    fn test_check_map() {
+        let digit = |c: char| c.is_ascii_digit();
+        assert_eq!(check_map("012\n345", digit, "digit"), Ok(()));
+        assert_eq!(
+            check_map("012\n3x5", digit, "digit"),
+            Err(ParseError::new(2, 2, "digit", "3x5"))
+        );
+        assert_eq!(
+            check_map("012\n3456", digit, "digit"),
+            Err(ParseError::new(2, 4, "end of row", "3456"))
+        );
+        assert_eq!(
+            check_map("012\n34", digit, "digit"),
+            Err(ParseError::new(2, 3, "digit", "34"))
+        );
+    }
+}
@@ -0,0 +1,288 @@
+use std::{
+    error::Error,
+    fs,
+    io::{self, Read},
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use aoc_runner::{ArcStr, Runner};
+
+use crate::{
+    inputs::InputStore,
+    output::{self, Summary},
+    parse::ParseError,
+};
+
+pub type Builder = fn(ArcStr) -> Result<Box<dyn Runner>, Box<dyn Error>>;
+
+pub struct Solver {
+    pub day: u8,
+    pub part: u8,
+    pub build: Builder,
+}
+
+impl Solver {
+    pub const fn new(day: u8, part: u8, build: Builder) -> Self {
+        Solver { day, part, build }
+    }
+}
+
+/// The `(day, part)` of every `#[aoc(dayN, partM)]` attribute in `source`
+fn declared(source: &str) -> impl Iterator<Item = (u8, u8)> + '_ {
+    source.lines().filter_map(|line| {
+        let rest = line.trim_start().strip_prefix("#[aoc(")?;
+        let mut args = rest.split([',', ')']).map(str::trim);
+        let day = args.next()?.strip_prefix("day")?.parse().ok()?;
+        let part = args.next()?.strip_prefix("part")?.parse().ok()?;
+        Some((day, part))
+    })
+}
+
+/// The solvers declared with `#[aoc]` in the sources of `dir` but missing from `solvers`, as
+/// `(day, part)`. The tables are written by hand, so each year checks its own in a test.
+pub fn unlisted(solvers: &[Solver], dir: &Path) -> io::Result<Vec<(u8, u8)>> {
+    let mut missing = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|extension| extension == "rs") {
+            missing.extend(
+                declared(&fs::read_to_string(&path)?)
+                    .filter(|&(day, part)| !solvers.iter().any(|s| (s.day, s.part) == (day, part))),
+            );
+        }
+    }
+    missing.sort_unstable();
+    Ok(missing)
+}
+
+/// Another implementation of a solver, benched next to it under `name`
+pub struct Variant {
+    pub day: u8,
+    pub part: u8,
+    pub name: &'static str,
+    pub build: Builder,
+}
+
+impl Variant {
+    pub const fn new(day: u8, part: u8, name: &'static str, build: Builder) -> Self {
+        Variant {
+            day,
+            part,
+            name,
+            build,
+        }
+    }
+}
+
+pub struct Year {
+    pub year: u32,
+    pub solvers: &'static [Solver],
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct Selection {
+    pub day: Option<u8>,
+    pub part: Option<u8>,
+}
+
+impl Selection {
+    pub fn matches(&self, solver: &Solver) -> bool {
+        self.day.is_none_or(|day| solver.day == day)
+            && self.part.is_none_or(|part| solver.part == part)
+    }
+}
+
+pub enum Outcome {
+    Solved {
+        answer: String,
+        parse: Duration,
+        solve: Duration,
+    },
+    Failed(String),
+}
+
+pub fn run(solver: &Solver, input: ArcStr) -> Outcome {
+    let start = Instant::now();
+    let runner = match panic::catch_unwind(|| (solver.build)(input)) {
+        Ok(Ok(runner)) => runner,
+        Ok(Err(e)) => return Outcome::Failed(failure("generating", e.as_ref())),
+        Err(_) => return Outcome::Failed("generator panicked".to_string()),
+    };
+    let parsed = Instant::now();
+
+    match panic::catch_unwind(AssertUnwindSafe(|| runner.try_run())) {
+        Ok(Ok(answer)) => Outcome::Solved {
+            answer: answer.to_string(),
+            parse: parsed - start,
+            solve: parsed.elapsed(),
+        },
+        Ok(Err(e)) => Outcome::Failed(failure("running", e.as_ref())),
+        Err(_) => Outcome::Failed("runner panicked".to_string()),
+    }
+}
+
+/// Shows the offending line when the error is, or wraps, a [`ParseError`]
+fn failure(stage: &str, error: &(dyn Error + 'static)) -> String {
+    let mut source = Some(error);
+    while let Some(e) = source {
+        if let Some(parse) = e.downcast_ref::<ParseError>() {
+            return format!("{stage}: {error}\n{}", parse.snippet());
+        }
+        source = e.source();
+    }
+
+    format!("{stage}: {error}")
+}
+
+/// Where to read a day's input from: a file, stdin (`-`), or the `store` when unset
+pub fn read_input(
+    source: Option<&str>,
+    store: &InputStore,
+    year: u32,
+    day: u8,
+) -> Result<ArcStr, String> {
+    let input = match source {
+        Some("-") => {
+            let mut buffer = String::new();
+            io::stdin()
+                .read_to_string(&mut buffer)
+                .map_err(|e| format!("failed to read stdin: {e}"))?;
+            buffer
+        }
+        Some(path) => fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?,
+        None => store.load(year, day).map_err(|e| e.to_string())?,
+    };
+
+    Ok(ArcStr::from(&input))
+}
+
+/// Runs every selected solver of `year`, handing each outcome to `each` as it completes
+pub fn solve_year(
+    year: &Year,
+    selection: Selection,
+    source: Option<&str>,
+    store: &InputStore,
+    mut each: impl FnMut(&Solver, Outcome),
+) {
+    // Stdin can only be consumed once, so a single day reuses what was read for its first part
+    let mut cached: Option<(u8, ArcStr)> = None;
+
+    for solver in year.solvers.iter().filter(|s| selection.matches(s)) {
+        let input = match &cached {
+            Some((day, input)) if *day == solver.day => Ok(input.clone()),
+            _ => read_input(source, store, year.year, solver.day),
+        };
+
+        let outcome = match input {
+            Ok(input) => {
+                cached = Some((solver.day, input.clone()));
+                run(solver, input)
+            }
+            Err(e) => Outcome::Failed(format!("reading input: {e}")),
+        };
+
+        each(solver, outcome);
+    }
+}
+
+pub fn run_year(
+    year: &Year,
+    selection: Selection,
+    source: Option<&str>,
+    store: &InputStore,
+    json: bool,
+) -> Summary {
+    let mut summary = Summary::new(year.year);
+
+    solve_year(year, selection, source, store, |solver, outcome| {
+        output::report(year.year, solver, &outcome, json);
+        summary.add(&outcome);
+    });
+
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn echo(input: ArcStr) -> Result<Box<dyn Runner>, Box<dyn Error>> {
+        struct Echo(ArcStr);
+
+        impl Runner for Echo {
+            fn r#gen(input: ArcStr) -> Self {
+                Echo(input)
+            }
+
+            fn run(&self) -> Box<dyn std::fmt::Display> {
+                let input: &str = std::borrow::Borrow::borrow(&self.0);
+                Box::new(input.parse::<u32>().unwrap() * 2)
+            }
+
+            fn bench(&self, _: fn(&dyn std::fmt::Display)) {}
+        }
+
+        Ok(Box::new(Echo::r#gen(input)))
+    }
+
+    #[test]
+    fn test_run() {
+        let solver = Solver::new(1, 1, echo);
+        let Outcome::Solved { answer, .. } = run(&solver, ArcStr::from("21\n")) else {
+            panic!("echo should solve")
+        };
+        assert_eq!(answer, "42");
+        assert!(matches!(
+            run(&solver, ArcStr::from("nope")),
+            Outcome::Failed(_)
+        ));
+
+        let error = ParseError::new(2, 3, "number", "ab");
+        assert_eq!(
+            failure("generating", &error),
+            "generating: line 2, column 3: expected number, found end of line\n2 | ab\n  |   ^ expected number"
+        );
+        assert!(failure("running", &error).starts_with("running: line 2, column 3"));
+    }
+
+    #[test]
+    fn test_unlisted() {
+        let source = "#[aoc(day3, part1)]
+fn a() {}
+#[aoc(day03, part2, Alt)]
+// #[aoc(day4, part1)]
+    #[aoc(day12, part1)]";
+        let found: Vec<(u8, u8)> = declared(source).collect();
+        assert_eq!(found, [(3, 1), (3, 2), (12, 1)]);
+
+        let dir = std::env::temp_dir().join(format!("aoc-unlisted-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day3.rs"), source).unwrap();
+        fs::write(dir.join("notes.txt"), "#[aoc(day4, part1)]").unwrap();
+        let solvers = [Solver::new(3, 1, echo), Solver::new(12, 1, echo)];
+        assert_eq!(unlisted(&solvers, &dir).unwrap(), [(3, 2)]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_selection() {
+        let solver = Solver::new(7, 2, echo);
+        assert!(Selection::default().matches(&solver));
+        assert!(
+            Selection {
+                day: Some(7),
+                part: None
+            }
+            .matches(&solver)
+        );
+        assert!(
+            !Selection {
+                day: Some(7),
+                part: Some(1)
+            }
+            .matches(&solver)
+        );
+    }
+}
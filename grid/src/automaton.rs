@@ -0,0 +1,411 @@
+//! Life-like cellular automata: a birth/survival rule over a Moore or von Neumann neighbourhood,
+//! on a bounded, toroidal or infinite board, with cells that can be pinned on or off.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Display},
+    mem,
+    str::FromStr,
+};
+
+use crate::{AROUND, CROSS, Grid};
+
+/// Cells are `(x, y)` like grid positions, but infinite boards reach into negative coordinates
+pub type Cell = (i64, i64);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleError {
+    Syntax(String),
+    /// `B0` on an infinite board would bring the whole plane to life
+    InfiniteBirth,
+}
+
+impl Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleError::Syntax(rule) => {
+                write!(f, "invalid rule '{rule}', expected B<digits>/S<digits>")
+            }
+            RuleError::InfiniteBirth => write!(f, "B0 rules need a finite board"),
+        }
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+/// Which neighbour counts bring a dead cell to life and which keep a live cell alive,
+/// as one bit per count
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    birth: u16,
+    survival: u16,
+}
+
+impl Rule {
+    /// Conway's Game of Life, `B3/S23`
+    pub const LIFE: Rule = Rule {
+        birth: 1 << 3,
+        survival: 1 << 2 | 1 << 3,
+    };
+
+    pub fn next(&self, alive: bool, neighbours: usize) -> bool {
+        let counts = if alive { self.survival } else { self.birth };
+        counts & 1 << neighbours != 0
+    }
+}
+
+impl FromStr for Rule {
+    type Err = RuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || RuleError::Syntax(s.to_string());
+        let counts = |digits: &str| {
+            digits
+                .chars()
+                .try_fold(0, |counts, c| match c.to_digit(10) {
+                    Some(n) if n <= 8 => Some(counts | 1 << n),
+                    _ => None,
+                })
+        };
+
+        let (mut birth, mut survival) = (None, None);
+        for part in s.split('/') {
+            let mut chars = part.chars();
+            let kind = chars.next().map(|c| c.to_ascii_uppercase());
+            let counts = counts(chars.as_str()).ok_or_else(error)?;
+            match kind {
+                Some('B') if birth.is_none() => birth = Some(counts),
+                Some('S') if survival.is_none() => survival = Some(counts),
+                _ => return Err(error()),
+            }
+        }
+
+        match (birth, survival) {
+            (Some(birth), Some(survival)) => Ok(Rule { birth, survival }),
+            _ => Err(error()),
+        }
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = |counts: u16| {
+            (0..=8)
+                .filter(|n| counts & 1 << n != 0)
+                .map(|n| n.to_string())
+                .collect::<String>()
+        };
+        write!(f, "B{}/S{}", digits(self.birth), digits(self.survival))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Neighbourhood {
+    /// The eight surrounding cells
+    #[default]
+    Moore,
+    /// The four orthogonal cells
+    VonNeumann,
+}
+
+impl Neighbourhood {
+    pub fn offsets(self) -> &'static [(isize, isize)] {
+        match self {
+            Neighbourhood::Moore => &AROUND,
+            Neighbourhood::VonNeumann => &CROSS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topology {
+    /// Cells past the edges are dead
+    Bounded,
+    /// Opposite edges are neighbours
+    Toroidal,
+    /// The starting grid sits at the origin of an unbounded plane
+    Infinite,
+}
+
+#[derive(Debug, Clone)]
+enum Board {
+    /// The generation being built is written to `next`, then the two swap
+    Dense {
+        cells: Grid<bool>,
+        next: Grid<bool>,
+        wrap: bool,
+    },
+    Sparse(HashSet<Cell>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Automaton {
+    rule: Rule,
+    neighbourhood: Neighbourhood,
+    board: Board,
+    pinned: Vec<(Cell, bool)>,
+    generation: usize,
+}
+
+impl Automaton {
+    pub fn new(start: &Grid<bool>, rule: Rule, topology: Topology) -> Result<Self, RuleError> {
+        let board = match topology {
+            Topology::Infinite if rule.next(false, 0) => return Err(RuleError::InfiniteBirth),
+            Topology::Infinite => Board::Sparse(
+                start
+                    .iter()
+                    .filter(|&(_, &alive)| alive)
+                    .map(|((x, y), _)| (x as i64, y as i64))
+                    .collect(),
+            ),
+            _ => Board::Dense {
+                cells: start.clone(),
+                next: start.clone(),
+                wrap: topology == Topology::Toroidal,
+            },
+        };
+
+        Ok(Automaton {
+            rule,
+            neighbourhood: Neighbourhood::default(),
+            board,
+            pinned: vec![],
+            generation: 0,
+        })
+    }
+
+    pub fn with_neighbourhood(mut self, neighbourhood: Neighbourhood) -> Self {
+        self.neighbourhood = neighbourhood;
+        self
+    }
+
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// The dense board's position of `cell`, panicking like grid indexing when it is outside
+    fn position(cells: &Grid<bool>, (x, y): Cell) -> (usize, usize) {
+        match (usize::try_from(x), usize::try_from(y)) {
+            (Ok(x), Ok(y)) if x < cells.width() && y < cells.height() => (x, y),
+            _ => panic!("({x}, {y}) out of bounds"),
+        }
+    }
+
+    pub fn get(&self, cell: Cell) -> bool {
+        match &self.board {
+            Board::Dense { cells, .. } => {
+                let (x, y) = cell;
+                match (usize::try_from(x), usize::try_from(y)) {
+                    (Ok(x), Ok(y)) => cells.get((x, y)).copied().unwrap_or(false),
+                    _ => false,
+                }
+            }
+            Board::Sparse(live) => live.contains(&cell),
+        }
+    }
+
+    pub fn set(&mut self, cell: Cell, alive: bool) {
+        match &mut self.board {
+            Board::Dense { cells, .. } => {
+                let position = Automaton::position(cells, cell);
+                cells[position] = alive;
+            }
+            Board::Sparse(live) if alive => {
+                live.insert(cell);
+            }
+            Board::Sparse(live) => {
+                live.remove(&cell);
+            }
+        }
+    }
+
+    /// Keeps `cell` on or off whatever the rule says, starting now
+    pub fn pin(&mut self, cell: Cell, alive: bool) {
+        self.set(cell, alive);
+        self.pinned.retain(|&(pinned, _)| pinned != cell);
+        self.pinned.push((cell, alive));
+    }
+
+    pub fn population(&self) -> usize {
+        match &self.board {
+            Board::Dense { cells, .. } => cells.count(|&alive| alive),
+            Board::Sparse(live) => live.len(),
+        }
+    }
+
+    /// The live cells, row by row
+    pub fn live_cells(&self) -> Vec<Cell> {
+        let mut live: Vec<Cell> = match &self.board {
+            Board::Dense { cells, .. } => cells
+                .iter()
+                .filter(|&(_, &alive)| alive)
+                .map(|((x, y), _)| (x as i64, y as i64))
+                .collect(),
+            Board::Sparse(live) => live.iter().copied().collect(),
+        };
+        live.sort_unstable_by_key(|&(x, y)| (y, x));
+        live
+    }
+
+    /// The cells of a bounded or toroidal board
+    pub fn grid(&self) -> Option<&Grid<bool>> {
+        match &self.board {
+            Board::Dense { cells, .. } => Some(cells),
+            Board::Sparse(_) => None,
+        }
+    }
+
+    pub fn step(&mut self) {
+        let offsets = self.neighbourhood.offsets();
+
+        match &mut self.board {
+            Board::Dense { cells, next, wrap } => {
+                let (width, height) = (cells.width() as isize, cells.height() as isize);
+
+                for ((x, y), &alive) in cells.iter() {
+                    let neighbours = offsets
+                        .iter()
+                        .filter(|&&(dx, dy)| {
+                            let (nx, ny) = (x as isize + dx, y as isize + dy);
+                            if *wrap {
+                                cells[(
+                                    nx.rem_euclid(width) as usize,
+                                    ny.rem_euclid(height) as usize,
+                                )]
+                            } else {
+                                cells.offset((x, y), (dx, dy)).is_some_and(|n| cells[n])
+                            }
+                        })
+                        .count();
+                    next[(x, y)] = self.rule.next(alive, neighbours);
+                }
+
+                mem::swap(cells, next);
+            }
+            Board::Sparse(live) => {
+                let mut counts: HashMap<Cell, usize> = live.iter().map(|&cell| (cell, 0)).collect();
+                for &(x, y) in live.iter() {
+                    for &(dx, dy) in offsets {
+                        *counts.entry((x + dx as i64, y + dy as i64)).or_default() += 1;
+                    }
+                }
+
+                *live = counts
+                    .into_iter()
+                    .filter(|&(cell, neighbours)| self.rule.next(live.contains(&cell), neighbours))
+                    .map(|(cell, _)| cell)
+                    .collect();
+            }
+        }
+
+        for (cell, alive) in mem::take(&mut self.pinned) {
+            self.set(cell, alive);
+            self.pinned.push((cell, alive));
+        }
+        self.generation += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn start(rows: &str) -> Grid<bool> {
+        Grid::parse(rows, |c| c == '#')
+    }
+
+    const GLIDER: &str = ".#....\n..#...\n###...\n......\n......\n......";
+
+    #[test]
+    fn test_rules() {
+        let highlife: Rule = "B36/S23".parse().unwrap();
+        assert!(highlife.next(false, 6) && highlife.next(true, 2) && !highlife.next(true, 6));
+        assert_eq!(highlife.to_string(), "B36/S23");
+        assert_eq!("s23/b3".parse(), Ok(Rule::LIFE));
+        assert_eq!("B/S".parse::<Rule>().unwrap().to_string(), "B/S");
+
+        for invalid in ["B3", "B39/S23", "B3/S2/S3", "X3/S23", "", "B3/"] {
+            assert_eq!(
+                invalid.parse::<Rule>(),
+                Err(RuleError::Syntax(invalid.to_string())),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_bounded() {
+        let mut blinker =
+            Automaton::new(&start("...\n###\n..."), Rule::LIFE, Topology::Bounded).unwrap();
+        blinker.step();
+        assert_eq!(blinker.live_cells(), [(1, 0), (1, 1), (1, 2)]);
+        blinker.step();
+        assert_eq!(blinker.live_cells(), [(0, 1), (1, 1), (2, 1)]);
+
+        // The glider dies against the corner
+        let mut glider = Automaton::new(&start(GLIDER), Rule::LIFE, Topology::Bounded).unwrap();
+        for _ in 0..24 {
+            glider.step();
+        }
+        assert_eq!(glider.population(), 4);
+        assert_eq!(glider.live_cells(), [(4, 4), (5, 4), (4, 5), (5, 5)]);
+    }
+
+    #[test]
+    fn test_toroidal() {
+        let mut glider = Automaton::new(&start(GLIDER), Rule::LIFE, Topology::Toroidal).unwrap();
+        for _ in 0..24 {
+            glider.step();
+        }
+        assert_eq!(glider.grid(), Some(&start(GLIDER)));
+        assert_eq!(glider.generation(), 24);
+    }
+
+    #[test]
+    fn test_infinite() {
+        let mut glider = Automaton::new(&start(GLIDER), Rule::LIFE, Topology::Infinite).unwrap();
+        for _ in 0..40 {
+            glider.step();
+        }
+        let moved: Vec<Cell> = Automaton::new(&start(GLIDER), Rule::LIFE, Topology::Bounded)
+            .unwrap()
+            .live_cells()
+            .into_iter()
+            .map(|(x, y)| (x + 10, y + 10))
+            .collect();
+        assert_eq!(glider.live_cells(), moved);
+        assert_eq!(glider.grid(), None);
+
+        let b0 = "B0/S".parse().unwrap();
+        assert_eq!(
+            Automaton::new(&start(GLIDER), b0, Topology::Infinite).unwrap_err(),
+            RuleError::InfiniteBirth
+        );
+    }
+
+    #[test]
+    fn test_von_neumann() {
+        // Every cell with an odd number of orthogonal neighbours is born or survives
+        let rule = "B13/S13".parse().unwrap();
+        let mut cross = Automaton::new(&start("#"), rule, Topology::Infinite)
+            .unwrap()
+            .with_neighbourhood(Neighbourhood::VonNeumann);
+        cross.step();
+        assert_eq!(cross.live_cells(), [(0, -1), (-1, 0), (1, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn test_pinned() {
+        let mut blinker =
+            Automaton::new(&start("...\n###\n..."), Rule::LIFE, Topology::Bounded).unwrap();
+        blinker.pin((0, 1), true);
+        blinker.pin((1, 0), false);
+        blinker.step();
+        assert_eq!(blinker.live_cells(), [(0, 1), (1, 1), (1, 2)]);
+        assert!(blinker.get((0, 1)) && !blinker.get((1, 0)) && !blinker.get((-1, 5)));
+    }
+}
@@ -0,0 +1,267 @@
+//! Grayscale pictures of `Grid<u8>`s, each cell's byte being its intensity. PGM and PPM are
+//! written by hand, PNG and GIF through pure Rust encoders, so nothing needs a display.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::Grid;
+
+/// The image formats, picked from the extension of the file written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Pgm,
+    Ppm,
+    Png,
+    Gif,
+}
+
+impl Format {
+    pub fn from_path(path: &Path) -> Option<Format> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        Some(match extension.as_str() {
+            "pgm" => Format::Pgm,
+            "ppm" => Format::Ppm,
+            "png" => Format::Png,
+            "gif" => Format::Gif,
+            _ => return None,
+        })
+    }
+}
+
+fn unknown_format() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "unknown image format, expected .pgm, .ppm, .png or .gif",
+    )
+}
+
+/// The 256 grays as a GIF palette, so that intensities are palette indices
+fn grays() -> Vec<u8> {
+    (0..=255).flat_map(|level| [level; 3]).collect()
+}
+
+fn gif_size(grid: &Grid<u8>) -> io::Result<(u16, u16)> {
+    match (grid.width.try_into(), grid.height.try_into()) {
+        (Ok(width), Ok(height)) => Ok((width, height)),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{}x{} is too large for a GIF", grid.width, grid.height),
+        )),
+    }
+}
+
+impl Grid<u8> {
+    pub fn encode(&self, format: Format, mut writer: impl Write) -> io::Result<()> {
+        match format {
+            Format::Pgm => {
+                write!(writer, "P5\n{} {}\n255\n", self.width, self.height)?;
+                writer.write_all(&self.cells)
+            }
+            Format::Ppm => {
+                write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+                let rgb: Vec<u8> = self.cells.iter().flat_map(|&level| [level; 3]).collect();
+                writer.write_all(&rgb)
+            }
+            Format::Png => {
+                let mut encoder = png::Encoder::new(writer, self.width as u32, self.height as u32);
+                encoder.set_color(png::ColorType::Grayscale);
+                encoder.set_depth(png::BitDepth::Eight);
+                encoder
+                    .write_header()
+                    .and_then(|mut png| png.write_image_data(&self.cells))
+                    .map_err(io::Error::other)
+            }
+            Format::Gif => {
+                let (width, height) = gif_size(self)?;
+                let mut encoder =
+                    gif::Encoder::new(writer, width, height, &grays()).map_err(io::Error::other)?;
+                encoder
+                    .write_frame(&gif::Frame::from_indexed_pixels(
+                        width,
+                        height,
+                        self.cells.as_slice(),
+                        None,
+                    ))
+                    .map_err(io::Error::other)
+            }
+        }
+    }
+
+    /// Writes the grid in the format named by the extension of `path`
+    pub fn write_image(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let format = Format::from_path(path).ok_or_else(unknown_format)?;
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.encode(format, &mut writer)?;
+        writer.flush()
+    }
+}
+
+enum Target {
+    Gif {
+        path: PathBuf,
+        encoder: Option<gif::Encoder<BufWriter<File>>>,
+    },
+    Frames {
+        path: PathBuf,
+    },
+}
+
+/// Successive grids, as the frames of one animated GIF or as numbered still images:
+/// `life.png` is written as `life-0000.png`, `life-0001.png`...
+pub struct Animation {
+    target: Target,
+    delay: u16,
+    frames: usize,
+}
+
+impl Animation {
+    pub fn new(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let target = match Format::from_path(&path) {
+            Some(Format::Gif) => Target::Gif {
+                path,
+                encoder: None,
+            },
+            Some(_) => Target::Frames { path },
+            None => return Err(unknown_format()),
+        };
+
+        Ok(Animation {
+            target,
+            delay: 10,
+            frames: 0,
+        })
+    }
+
+    /// How long a GIF shows each frame, in hundredths of a second
+    pub fn delay(mut self, centiseconds: u16) -> Self {
+        self.delay = centiseconds;
+        self
+    }
+
+    /// The file frame `n` goes to when writing still images
+    pub fn frame_path(path: &Path, n: usize) -> PathBuf {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let mut name = format!("{stem}-{n:04}");
+        if let Some(extension) = path.extension() {
+            name = format!("{name}.{}", extension.to_string_lossy());
+        }
+        path.with_file_name(name)
+    }
+
+    pub fn push(&mut self, grid: &Grid<u8>) -> io::Result<()> {
+        match &mut self.target {
+            Target::Gif { path, encoder } => {
+                let (width, height) = gif_size(grid)?;
+                let encoder = match encoder {
+                    Some(encoder) => encoder,
+                    None => {
+                        let file = BufWriter::new(File::create(path)?);
+                        let mut created = gif::Encoder::new(file, width, height, &grays())
+                            .map_err(io::Error::other)?;
+                        created
+                            .set_repeat(gif::Repeat::Infinite)
+                            .map_err(io::Error::other)?;
+                        encoder.insert(created)
+                    }
+                };
+
+                let mut frame =
+                    gif::Frame::from_indexed_pixels(width, height, grid.cells.as_slice(), None);
+                frame.delay = self.delay;
+                encoder.write_frame(&frame).map_err(io::Error::other)?;
+            }
+            Target::Frames { path } => {
+                grid.write_image(Animation::frame_path(path, self.frames))?;
+            }
+        }
+
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Completes the files and returns how many frames were written
+    pub fn finish(self) -> io::Result<usize> {
+        if let Target::Gif {
+            encoder: Some(encoder),
+            ..
+        } = self.target
+        {
+            encoder.into_inner().map_err(io::Error::other)?.flush()?;
+        }
+
+        Ok(self.frames)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkerboard() -> Grid<u8> {
+        Grid::from_rows([[0, 128, 255], [255, 128, 0]])
+    }
+
+    #[test]
+    fn test_netpbm() {
+        let mut pgm = vec![];
+        checkerboard().encode(Format::Pgm, &mut pgm).unwrap();
+        assert_eq!(pgm, b"P5\n3 2\n255\n\x00\x80\xff\xff\x80\x00");
+
+        let mut ppm = vec![];
+        checkerboard().encode(Format::Ppm, &mut ppm).unwrap();
+        assert_eq!(&ppm[..11], b"P6\n3 2\n255\n");
+        assert_eq!(&ppm[11..17], [0, 0, 0, 128, 128, 128]);
+    }
+
+    #[test]
+    fn test_png() {
+        let mut bytes = vec![];
+        checkerboard().encode(Format::Png, &mut bytes).unwrap();
+
+        let mut reader = png::Decoder::new(io::Cursor::new(bytes))
+            .read_info()
+            .unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((info.width, info.height), (3, 2));
+        assert_eq!(pixels, [0, 128, 255, 255, 128, 0]);
+    }
+
+    #[test]
+    fn test_animation() {
+        let dir = std::env::temp_dir().join(format!("aoc-grid-image-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut gif = Animation::new(dir.join("life.gif")).unwrap().delay(5);
+        let mut frames = Animation::new(dir.join("life.pgm")).unwrap();
+        for level in [0, 255] {
+            let grid = Grid::new(4, 4, level);
+            gif.push(&grid).unwrap();
+            frames.push(&grid).unwrap();
+        }
+        assert_eq!(gif.finish().unwrap(), 2);
+        assert_eq!(frames.finish().unwrap(), 2);
+
+        let mut decoder = gif::DecodeOptions::new()
+            .read_info(File::open(dir.join("life.gif")).unwrap())
+            .unwrap();
+        let mut delays = vec![];
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            delays.push((frame.delay, frame.buffer[0]));
+        }
+        assert_eq!(delays, [(5, 0), (5, 255)]);
+        assert!(
+            std::fs::read(dir.join("life-0001.pgm"))
+                .unwrap()
+                .ends_with(&[255; 16])
+        );
+        assert!(Animation::new(dir.join("life.bmp")).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
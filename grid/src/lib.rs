@@ -0,0 +1,318 @@
+use std::{
+    fmt::{self, Display},
+    ops::{Index, IndexMut},
+};
+
+pub mod automaton;
+#[cfg(feature = "image")]
+pub mod image;
+
+/// Positions are `(x, y)`, with `(0, 0)` the top left corner and `y` growing downwards
+pub type Position = (usize, usize);
+
+pub const CROSS: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+pub const AROUND: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Every cell blown up to a `factor` by `factor` square
+    pub fn scaled(&self, factor: usize) -> Self {
+        Grid {
+            width: self.width * factor,
+            height: self.height * factor,
+            cells: self
+                .rows()
+                .flat_map(|row| {
+                    let row: Vec<T> = row
+                        .iter()
+                        .flat_map(|cell| std::iter::repeat_n(cell.clone(), factor))
+                        .collect();
+                    std::iter::repeat_n(row, factor).flatten()
+                })
+                .collect(),
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its rows, all of them must have the same length
+    pub fn from_rows<R: IntoIterator<Item = T>>(rows: impl IntoIterator<Item = R>) -> Self {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+
+        for row in rows {
+            let before = cells.len();
+            cells.extend(row);
+            let length = cells.len() - before;
+
+            assert!(
+                *width.get_or_insert(length) == length,
+                "row {height} has {length} cells, expected {}",
+                width.unwrap()
+            );
+            height += 1;
+        }
+
+        Grid {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        }
+    }
+
+    /// Parses a character map, one row per line, converting each character with `cell`
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> T) -> Self {
+        Grid::from_rows(
+            input
+                .lines()
+                .map(|line| line.chars().map(&mut cell).collect::<Vec<T>>()),
+        )
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (x, y): Position) -> Option<&T> {
+        if x < self.width && y < self.height {
+            Some(&self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, (x, y): Position) -> Option<&mut T> {
+        if x < self.width && y < self.height {
+            Some(&mut self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    /// Moves `(dx, dy)` away from `position`, if that lands inside the grid
+    pub fn offset(&self, (x, y): Position, (dx, dy): (isize, isize)) -> Option<Position> {
+        let nx = x.checked_add_signed(dx).filter(|&nx| nx < self.width)?;
+        let ny = y.checked_add_signed(dy).filter(|&ny| ny < self.height)?;
+
+        Some((nx, ny))
+    }
+
+    /// Up, right, down and left neighbours that are inside the grid
+    pub fn neighbours4(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        CROSS
+            .into_iter()
+            .filter_map(move |delta| self.offset(position, delta))
+    }
+
+    /// All eight surrounding neighbours that are inside the grid
+    pub fn neighbours8(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        AROUND
+            .into_iter()
+            .filter_map(move |delta| self.offset(position, delta))
+    }
+
+    /// Every position, row by row
+    pub fn positions(&self) -> impl Iterator<Item = Position> + use<T> {
+        let width = self.width;
+        (0..self.width * self.height).map(move |i| (i % width, i / width))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<Position> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(position, _)| position)
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1)).take(self.height)
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {x} out of bounds");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Diagonals running down and to the right, starting with the bottom left corner
+    pub fn diagonals(&self) -> impl Iterator<Item = Vec<&T>> {
+        let (width, height) = (self.width as isize, self.height as isize);
+
+        (1 - height..width).map(move |d| {
+            (0..height)
+                .filter_map(|y| self.get(((y + d).try_into().ok()?, y as usize)))
+                .collect()
+        })
+    }
+
+    /// Diagonals running down and to the left, starting with the top left corner
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = Vec<&T>> {
+        let (width, height) = (self.width as isize, self.height as isize);
+
+        (0..width + height - 1).map(move |s| {
+            (0..height)
+                .filter_map(|y| self.get(((s - y).try_into().ok()?, y as usize)))
+                .collect()
+        })
+    }
+
+    pub fn count(&self, predicate: impl Fn(&T) -> bool) -> usize {
+        self.cells.iter().filter(|cell| predicate(cell)).count()
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Draws the grid one character per cell, like the puzzle inputs do
+    pub fn render(&self, cell: impl Fn(&T) -> char) -> String {
+        let mut output = String::with_capacity((self.width + 1) * self.height);
+
+        for row in self.rows() {
+            output.extend(row.iter().map(&cell));
+            output.push('\n');
+        }
+
+        output
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): Position) -> &Self::Output {
+        assert!(
+            x < self.width && y < self.height,
+            "({x}, {y}) out of bounds"
+        );
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, (x, y): Position) -> &mut Self::Output {
+        assert!(
+            x < self.width && y < self.height,
+            "({x}, {y}) out of bounds"
+        );
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?
+            }
+            writeln!(f)?
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "abc
+def";
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse(INPUT, |c| c);
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], 'f');
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.to_string(), "abc\ndef\n");
+        assert_eq!(
+            grid.map(|&c| c == 'e')
+                .render(|&b| if b { '#' } else { '.' }),
+            "...\n.#.\n"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_ragged_rows() {
+        Grid::parse("abc\nde", |c| c);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::new(3, 3, 0);
+        assert_eq!(
+            grid.neighbours4((0, 0)).collect::<Vec<_>>(),
+            [(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbours4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbours8((0, 0)).count(), 3);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+        assert_eq!(grid.neighbours8((2, 1)).count(), 5);
+    }
+
+    #[test]
+    fn test_views() {
+        let grid = Grid::parse(INPUT, |c| c);
+        assert_eq!(grid.row(1), ['d', 'e', 'f']);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.columns().count(), 3);
+        assert_eq!(
+            grid.diagonals()
+                .map(|d| d.into_iter().collect::<String>())
+                .collect::<Vec<_>>(),
+            ["d", "ae", "bf", "c"]
+        );
+        assert_eq!(
+            grid.anti_diagonals()
+                .map(|d| d.into_iter().collect::<String>())
+                .collect::<Vec<_>>(),
+            ["a", "bd", "ce", "f"]
+        );
+        assert_eq!(grid.position(|&c| c == 'e'), Some((1, 1)));
+        assert_eq!(grid.scaled(2).row(3), ['d', 'd', 'e', 'e', 'f', 'f']);
+    }
+}
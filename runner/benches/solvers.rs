@@ -0,0 +1,295 @@
+//! Benches every registered solver and variant, generator and runner separately, on the cached
+//! inputs. Besides Criterion's own reports, the means are written to `bench-results.json` and
+//! `bench-results.csv` next to them (or in `$AOC_BENCH_RESULTS`) for diffing between commits.
+
+use std::{
+    fmt::Write as _,
+    fs,
+    hint::black_box,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use advent_of_code_common::{
+    inputs::InputStore,
+    output::json_string,
+    solver::{Builder, Variant, Year},
+};
+use aoc_runner::{ArcStr, Runner};
+use criterion::{BenchmarkId, Criterion};
+use regex::Regex;
+
+const ROOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/..");
+
+fn years() -> Vec<(Year, &'static [Variant])> {
+    vec![
+        (
+            Year {
+                year: advent_of_code_2015::YEAR,
+                solvers: advent_of_code_2015::SOLVERS,
+            },
+            &[],
+        ),
+        (
+            Year {
+                year: advent_of_code_2024::YEAR,
+                solvers: advent_of_code_2024::SOLVERS,
+            },
+            &[],
+        ),
+        (
+            Year {
+                year: advent_of_code_2025::YEAR,
+                solvers: advent_of_code_2025::SOLVERS,
+            },
+            advent_of_code_2025::VARIANTS,
+        ),
+    ]
+}
+
+/// `$AOC_BENCH_FILTER`, a regular expression over the bench ids. Criterion only applies its own
+/// filter once a bench is registered, after the solver has been built and run for its answer, so
+/// this one skips the solvers it rejects before their input is even loaded.
+fn filter_from_env() -> Option<Regex> {
+    let filter = std::env::var("AOC_BENCH_FILTER").ok()?;
+    Some(Regex::new(&filter).unwrap_or_else(|e| {
+        panic!("$AOC_BENCH_FILTER '{filter}' is not a regular expression: {e}")
+    }))
+}
+
+/// One benchmark as laid out by Criterion: `<group>/<stage>[/<variant>]`
+struct Bench {
+    year: u32,
+    day: u8,
+    part: u8,
+    stage: &'static str,
+    variant: Option<&'static str>,
+}
+
+impl Bench {
+    fn group(year: u32, day: u8, part: u8) -> String {
+        format!("{year}-day{day:02}-part{part}")
+    }
+
+    /// The id Criterion and `$AOC_BENCH_FILTER` match their filters against
+    fn id(&self) -> String {
+        let group = Bench::group(self.year, self.day, self.part);
+        match self.variant {
+            Some(variant) => format!("{group}/{}/{variant}", self.stage),
+            None => format!("{group}/{}", self.stage),
+        }
+    }
+
+    /// Whether any stage of a solver or variant passes the filter, if there is one
+    fn selected(
+        filter: Option<&Regex>,
+        (year, day, part): (u32, u8, u8),
+        variant: Option<&'static str>,
+    ) -> bool {
+        filter.is_none_or(|filter| {
+            ["parse", "solve"].into_iter().any(|stage| {
+                let id = Bench {
+                    year,
+                    day,
+                    part,
+                    stage,
+                    variant,
+                }
+                .id();
+                filter.is_match(&id)
+            })
+        })
+    }
+
+    fn estimates(&self, dir: &Path) -> PathBuf {
+        let mut path = dir
+            .join(Bench::group(self.year, self.day, self.part))
+            .join(self.stage);
+        if let Some(variant) = self.variant {
+            path.push(variant);
+        }
+        path.join("new").join("estimates.json")
+    }
+}
+
+/// Builds and runs a solver once, for its answer
+fn solve(
+    (year, day, part): (u32, u8, u8),
+    build: Builder,
+    input: &ArcStr,
+) -> Option<(Box<dyn Runner>, String)> {
+    let answer = build(input.clone()).and_then(|runner| {
+        let answer = runner.try_run()?.to_string();
+        Ok((runner, answer))
+    });
+    answer
+        .inspect_err(|e| eprintln!("Skipping {year} day {day} part {part}: {e}"))
+        .ok()
+}
+
+fn bench_solver(
+    criterion: &mut Criterion,
+    benches: &mut Vec<Bench>,
+    (year, day, part): (u32, u8, u8),
+    variant: Option<&'static str>,
+    build: Builder,
+    input: &ArcStr,
+) -> Option<String> {
+    let (runner, answer) = solve((year, day, part), build, input)?;
+
+    let mut group = criterion.benchmark_group(Bench::group(year, day, part));
+    group.sample_size(10);
+
+    for stage in ["parse", "solve"] {
+        let id = match variant {
+            Some(variant) => BenchmarkId::new(stage, variant),
+            None => BenchmarkId::from_parameter(stage),
+        };
+
+        match stage {
+            "parse" => group.bench_function(id, |b| b.iter(|| build(black_box(input.clone())))),
+            _ => group.bench_function(id, |b| b.iter(|| black_box(runner.try_run()))),
+        };
+
+        benches.push(Bench {
+            year,
+            day,
+            part,
+            stage,
+            variant,
+        });
+    }
+
+    group.finish();
+    Some(answer)
+}
+
+fn bench_all(criterion: &mut Criterion, filter: Option<&Regex>) -> Vec<Bench> {
+    let mut benches = vec![];
+
+    for (year, variants) in years() {
+        let store = InputStore::new(format!("{ROOT}/{}/input", year.year));
+
+        for solver in year.solvers {
+            let key = (year.year, solver.day, solver.part);
+            let variants: Vec<&Variant> = variants
+                .iter()
+                .filter(|v| (v.day, v.part) == (solver.day, solver.part))
+                .filter(|v| Bench::selected(filter, key, Some(v.name)))
+                .collect();
+            let benched = Bench::selected(filter, key, None);
+            if !benched && variants.is_empty() {
+                continue;
+            }
+
+            let input = match store.load(year.year, solver.day) {
+                Ok(input) => ArcStr::from(&input),
+                Err(e) => {
+                    eprintln!("Skipping {} day {}: {e}", year.year, solver.day);
+                    continue;
+                }
+            };
+
+            // The variants are checked against the solver even when it is not benched itself
+            let answer = if benched {
+                bench_solver(criterion, &mut benches, key, None, solver.build, &input)
+            } else {
+                solve(key, solver.build, &input).map(|(_, answer)| answer)
+            };
+
+            for variant in variants {
+                let other = bench_solver(
+                    criterion,
+                    &mut benches,
+                    key,
+                    Some(variant.name),
+                    variant.build,
+                    &input,
+                );
+                assert_eq!(
+                    other, answer,
+                    "variant '{}' of {} day {} part {} disagrees",
+                    variant.name, year.year, solver.day, solver.part
+                );
+            }
+        }
+    }
+
+    benches
+}
+
+/// The mean and standard deviation in nanoseconds, if Criterion measured the bench during this run
+fn read_estimates(path: &Path, since: SystemTime) -> Option<(f64, f64)> {
+    if fs::metadata(path).and_then(|m| m.modified()).ok()? < since {
+        return None;
+    }
+
+    let estimates: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+    let point = |name: &str| estimates[name]["point_estimate"].as_f64();
+    Some((point("mean")?, point("std_dev")?))
+}
+
+fn write_results(dir: &Path, since: SystemTime, benches: &[Bench]) {
+    let mut json = String::from("[\n");
+    let mut csv = String::from("year,day,part,stage,variant,mean_ns,std_dev_ns\n");
+
+    for bench in benches {
+        let Some((mean, std_dev)) = read_estimates(&bench.estimates(dir), since) else {
+            continue;
+        };
+        let Bench {
+            year,
+            day,
+            part,
+            stage,
+            variant,
+        } = bench;
+
+        if json.len() > 2 {
+            json.push_str(",\n");
+        }
+        write!(
+            json,
+            r#"  {{"year":{year},"day":{day},"part":{part},"stage":"{stage}","variant":{},"mean_ns":{mean:.1},"std_dev_ns":{std_dev:.1}}}"#,
+            variant.map_or("null".to_string(), json_string)
+        )
+        .unwrap();
+        writeln!(
+            csv,
+            "{year},{day},{part},{stage},{},{mean:.1},{std_dev:.1}",
+            variant.unwrap_or("")
+        )
+        .unwrap();
+    }
+    json.push_str("\n]\n");
+
+    if csv.lines().count() == 1 {
+        return;
+    }
+
+    let out = std::env::var_os("AOC_BENCH_RESULTS").map_or(dir.to_path_buf(), PathBuf::from);
+    for (name, contents) in [("bench-results.json", json), ("bench-results.csv", csv)] {
+        let path = out.join(name);
+        match fs::create_dir_all(&out).and_then(|_| fs::write(&path, contents)) {
+            Ok(()) => println!("Results written to {}", path.display()),
+            Err(e) => eprintln!("{}: {e}", path.display()),
+        }
+    }
+}
+
+fn main() {
+    let dir = std::env::var_os("CRITERION_HOME").map_or(
+        Path::new(ROOT).join("target").join("criterion"),
+        PathBuf::from,
+    );
+    let since = SystemTime::now();
+
+    let mut criterion = Criterion::default()
+        .output_directory(&dir)
+        .configure_from_args();
+    let benches = bench_all(&mut criterion, filter_from_env().as_ref());
+    criterion.final_summary();
+
+    write_results(&dir, since, &benches);
+}
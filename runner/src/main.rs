@@ -0,0 +1,283 @@
+use std::process::ExitCode;
+
+use advent_of_code_common::{
+    answers::{Answers, verify_year},
+    inputs::InputStore,
+    output::{summary_table, verify_table},
+    solver::{Selection, Year, run_year},
+};
+
+const USAGE: &str = "Usage: aoc run YEAR [DAY [PART]] [--input <FILE|->] [--inputs DIR] [--json]
+       aoc run --all [--inputs DIR] [--json]
+       aoc verify <YEAR [DAY [PART]] | --all> [--inputs DIR] [--answers DIR] [--record] [--json]
+
+  run              print the answers with their timings
+  verify           compare the answers with the accepted ones in DIR/<YEAR>.toml
+
+  YEAR, DAY, PART  run a whole year, a single day or a single part
+  --all            run every year in sequence and print a summary table
+  --input FILE     read the puzzle input from FILE, or from stdin with '-'
+  --inputs DIR     read inputs from DIR/<YEAR>/day<DAY>.txt
+                   (default: <YEAR>/input/<YEAR>/day<DAY>.txt)
+                   missing inputs are downloaded when AOC_SESSION is set
+  --answers DIR    read accepted answers from DIR/<YEAR>.toml (default: answers)
+  --record         store the answers that have no accepted value yet
+  --json           print one JSON object per solver instead of text";
+
+fn years() -> Vec<Year> {
+    vec![
+        Year {
+            year: advent_of_code_2015::YEAR,
+            solvers: advent_of_code_2015::SOLVERS,
+        },
+        Year {
+            year: advent_of_code_2024::YEAR,
+            solvers: advent_of_code_2024::SOLVERS,
+        },
+        Year {
+            year: advent_of_code_2025::YEAR,
+            solvers: advent_of_code_2025::SOLVERS,
+        },
+    ]
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+enum Command {
+    #[default]
+    Run,
+    Verify,
+}
+
+#[derive(Default)]
+struct Options {
+    command: Command,
+    all: bool,
+    year: Option<u32>,
+    selection: Selection,
+    input: Option<String>,
+    inputs: Option<String>,
+    answers: Option<String>,
+    record: bool,
+    json: bool,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let command = match args.next().as_deref() {
+        Some("run") => Command::Run,
+        Some("verify") => Command::Verify,
+        Some(command) => return Err(format!("unknown command '{command}'")),
+        None => return Err("missing command".to_string()),
+    };
+
+    let mut options = Options {
+        command,
+        ..Default::default()
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => options.all = true,
+            "--json" => options.json = true,
+            "--record" => options.record = true,
+            "--input" | "-i" => {
+                options.input = Some(args.next().ok_or("--input expects a path or '-'")?)
+            }
+            "--inputs" => options.inputs = Some(args.next().ok_or("--inputs expects a directory")?),
+            "--answers" => {
+                options.answers = Some(args.next().ok_or("--answers expects a directory")?)
+            }
+            _ if options.year.is_none() => {
+                options.year = Some(arg.parse().map_err(|_| format!("invalid year '{arg}'"))?)
+            }
+            _ if options.selection.day.is_none() => {
+                options.selection.day =
+                    Some(arg.parse().map_err(|_| format!("invalid day '{arg}'"))?)
+            }
+            _ if options.selection.part.is_none() => {
+                options.selection.part =
+                    Some(arg.parse().map_err(|_| format!("invalid part '{arg}'"))?)
+            }
+            _ => return Err(format!("unexpected argument '{arg}'")),
+        }
+    }
+
+    if options.all == options.year.is_some() {
+        return Err("expected either a YEAR or --all".to_string());
+    }
+
+    if options.input.is_some() && options.selection.day.is_none() {
+        return Err("--input needs a DAY, every day has its own input".to_string());
+    }
+
+    match options.command {
+        Command::Run if options.answers.is_some() || options.record => {
+            return Err("--answers and --record only apply to verify".to_string());
+        }
+        Command::Verify if options.input.is_some() => {
+            return Err("verify reads the stored inputs, --input is not supported".to_string());
+        }
+        _ => {}
+    }
+
+    Ok(options)
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let years: Vec<Year> = years()
+        .into_iter()
+        .filter(|y| options.year.is_none_or(|year| y.year == year))
+        .filter(|y| y.solvers.iter().any(|s| options.selection.matches(s)))
+        .collect();
+
+    if years.is_empty() {
+        eprintln!("No solver registered for the requested year/day/part\n\n{USAGE}");
+        return ExitCode::from(2);
+    }
+
+    match options.command {
+        Command::Run => run(&options, &years),
+        Command::Verify => verify(&options, &years),
+    }
+}
+
+fn input_store(options: &Options, year: &Year) -> InputStore {
+    match &options.inputs {
+        Some(dir) => InputStore::new(dir),
+        None => InputStore::new(format!("{}/input", year.year)),
+    }
+    .fetch_from_env()
+}
+
+fn run(options: &Options, years: &[Year]) -> ExitCode {
+    let mut summaries = vec![];
+    for year in years {
+        if !options.json {
+            println!("Advent of code {}\n", year.year);
+        }
+
+        summaries.push(run_year(
+            year,
+            options.selection,
+            options.input.as_deref(),
+            &input_store(options, year),
+            options.json,
+        ));
+    }
+
+    if options.all && !options.json {
+        println!("{}", summary_table(&summaries));
+    }
+
+    if summaries.iter().any(|s| s.failed > 0) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn verify(options: &Options, years: &[Year]) -> ExitCode {
+    let dir = options.answers.as_deref().unwrap_or("answers");
+
+    let mut tallies = vec![];
+    for year in years {
+        let mut answers = match Answers::load(dir, year.year) {
+            Ok(answers) => answers,
+            Err(e) => {
+                eprintln!("Failed to load the answers: {e}");
+                return ExitCode::FAILURE;
+            }
+        };
+
+        if !options.json {
+            println!("Advent of code {}\n", year.year);
+        }
+
+        let tally = verify_year(
+            year,
+            options.selection,
+            &input_store(options, year),
+            &mut answers,
+            options.record,
+            options.json,
+        );
+
+        if options.record
+            && tally.missing > 0
+            && let Err(e) = answers.save(dir, year.year)
+        {
+            eprintln!("Failed to record the answers: {e}");
+            return ExitCode::FAILURE;
+        }
+
+        if !options.json {
+            println!();
+        }
+        tallies.push(tally);
+    }
+
+    if !options.json {
+        println!("{}", verify_table(&tallies));
+    }
+
+    if tallies.iter().any(|t| t.wrong > 0 || t.failed > 0) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> impl Iterator<Item = String> {
+        list.iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let options = parse_args(args(&["run", "2015", "7", "1"])).unwrap();
+        assert_eq!(options.year, Some(2015));
+        assert_eq!(
+            (options.selection.day, options.selection.part),
+            (Some(7), Some(1))
+        );
+
+        let options = parse_args(args(&["run", "--all", "--json"])).unwrap();
+        assert!(options.all && options.json);
+
+        assert!(parse_args(args(&["run"])).is_err());
+        assert!(parse_args(args(&["run", "--all", "2015"])).is_err());
+        assert!(parse_args(args(&["walk", "2015"])).is_err());
+        assert!(parse_args(args(&["run", "2015", "--input", "day7.txt"])).is_err());
+
+        let options = parse_args(args(&["verify", "2025", "--answers", "a", "--record"])).unwrap();
+        assert_eq!(options.command, Command::Verify);
+        assert_eq!(options.answers.as_deref(), Some("a"));
+        assert!(options.record);
+
+        assert!(parse_args(args(&["run", "2025", "--record"])).is_err());
+        assert!(parse_args(args(&["verify", "2025", "7", "--input", "-"])).is_err());
+    }
+
+    #[test]
+    fn test_registered_years() {
+        let years = years();
+        assert_eq!(
+            years.iter().map(|y| y.year).collect::<Vec<_>>(),
+            [2015, 2024, 2025]
+        );
+        assert!(years[0].solvers.iter().any(|s| (s.day, s.part) == (7, 1)));
+    }
+}